@@ -0,0 +1,214 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use serde::Serialize;
+
+// Wraps the system allocator to count allocations made by each thread. The
+// counting only happens once `enable` is called, so runs that don't ask for
+// allocation stats pay for one relaxed load per allocation. Nothing is counted
+// unless the binary installs this as its `#[global_allocator]`.
+pub struct Counting;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static ALLOCATIONS: Cell<u64> = const { Cell::new(0) };
+    static BYTES: Cell<u64> = const { Cell::new(0) };
+    // Bytes allocated minus bytes freed by this thread. Can go negative when
+    // the thread frees memory another thread allocated.
+    static LIVE: Cell<i64> = const { Cell::new(0) };
+    static PEAK: Cell<i64> = const { Cell::new(0) };
+}
+
+fn record_alloc(size: usize) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    // try_with fails while the thread is being torn down, in which case the
+    // allocation just goes uncounted.
+    let _ = ALLOCATIONS.try_with(|x| x.set(x.get() + 1));
+    let _ = BYTES.try_with(|x| x.set(x.get() + size as u64));
+    let _ = LIVE.try_with(|live| {
+        live.set(live.get() + size as i64);
+        let _ = PEAK.try_with(|peak| peak.set(peak.get().max(live.get())));
+    });
+}
+
+fn record_dealloc(size: usize) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    let _ = LIVE.try_with(|x| x.set(x.get() - size as i64));
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record_alloc(layout.size());
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        record_alloc(layout.size());
+        unsafe { System.alloc_zeroed(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        record_dealloc(layout.size());
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        // Counted as freeing the old block and allocating a new one.
+        record_dealloc(layout.size());
+        record_alloc(new_size);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+// Allocations made by some piece of code.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Usage {
+    pub allocations: u64,
+    pub bytes: u64,
+    // Most memory held at once, counting from the start of the measurement.
+    pub peak: u64,
+    // Memory still held at the end, e.g. a parsed input.
+    pub retained: u64,
+}
+
+impl Usage {
+    // The usage of running `self` and then `next`, with whatever `self`
+    // retained still held during `next`.
+    pub fn then(&self, next: &Usage) -> Usage {
+        Usage {
+            allocations: self.allocations + next.allocations,
+            bytes: self.bytes + next.bytes,
+            peak: self.peak.max(self.retained + next.peak),
+            retained: self.retained + next.retained,
+        }
+    }
+}
+
+impl std::fmt::Display for Usage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} allocs, {} allocated, {} peak",
+            self.allocations,
+            format_bytes(self.bytes),
+            format_bytes(self.peak)
+        )
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+// Measures the allocations this thread makes between `start` and `finish`.
+pub struct Tracker {
+    allocations: u64,
+    bytes: u64,
+    live: i64,
+}
+
+impl Tracker {
+    // Returns None when counting is not enabled.
+    pub fn start() -> Option<Tracker> {
+        if !enabled() {
+            return None;
+        }
+
+        let live = LIVE.with(|x| x.get());
+        PEAK.with(|x| x.set(live));
+        Some(Tracker {
+            allocations: ALLOCATIONS.with(|x| x.get()),
+            bytes: BYTES.with(|x| x.get()),
+            live,
+        })
+    }
+
+    pub fn finish(self) -> Usage {
+        Usage {
+            allocations: ALLOCATIONS.with(|x| x.get()) - self.allocations,
+            bytes: BYTES.with(|x| x.get()) - self.bytes,
+            peak: (PEAK.with(|x| x.get()) - self.live).max(0) as u64,
+            retained: (LIVE.with(|x| x.get()) - self.live).max(0) as u64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The test binary doesn't get the one `main` installs.
+    #[global_allocator]
+    static GLOBAL: Counting = Counting;
+
+    #[test]
+    fn tracker_test() {
+        enable();
+
+        let tracker = Tracker::start().unwrap();
+        let kept: Vec<u8> = Vec::with_capacity(1000);
+        drop(Vec::<u8>::with_capacity(5000));
+        let usage = tracker.finish();
+
+        assert_eq!(usage.allocations, 2);
+        assert_eq!(usage.bytes, 6000);
+        assert_eq!(usage.peak, 6000);
+        assert_eq!(usage.retained, 1000);
+        drop(kept);
+    }
+
+    #[test]
+    fn then_test() {
+        let parse = Usage {
+            allocations: 1,
+            bytes: 100,
+            peak: 150,
+            retained: 100,
+        };
+        let solve = Usage {
+            allocations: 2,
+            bytes: 80,
+            peak: 80,
+            retained: 0,
+        };
+        let total = parse.then(&solve);
+        assert_eq!(total.allocations, 3);
+        assert_eq!(total.bytes, 180);
+        assert_eq!(total.peak, 180);
+        assert_eq!(total.retained, 100);
+    }
+
+    #[test]
+    fn format_test() {
+        assert_eq!(format_bytes(10), "10 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 << 30), "3.0 GiB");
+    }
+}
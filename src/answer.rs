@@ -0,0 +1,169 @@
+use std::{cmp::Ordering, fmt::Display};
+
+use num_bigint::{BigInt, BigUint};
+use serde::{Serialize, Serializer};
+
+// The answer to a problem. Numeric answers compare by value regardless of
+// which variant holds them, so `Unsigned(5) == Signed(5)`.
+#[derive(Clone, Debug)]
+pub enum Answer {
+    Unsigned(u64),
+    Signed(i64),
+    Big(BigInt),
+    Text(String),
+}
+
+impl Answer {
+    // Reads an answer from text, such as a recorded answer, using the
+    // narrowest variant that can hold it.
+    pub fn parse(s: &str) -> Self {
+        let s = s.trim();
+        if let Ok(n) = s.parse() {
+            Answer::Unsigned(n)
+        } else if let Ok(n) = s.parse() {
+            Answer::Signed(n)
+        } else if let Ok(n) = s.parse() {
+            Answer::Big(n)
+        } else {
+            Answer::Text(s.to_string())
+        }
+    }
+
+    pub fn to_bigint(&self) -> Option<BigInt> {
+        match self {
+            Answer::Unsigned(n) => Some((*n).into()),
+            Answer::Signed(n) => Some((*n).into()),
+            Answer::Big(n) => Some(n.clone()),
+            Answer::Text(_) => None,
+        }
+    }
+
+    // Orders two numeric answers. Returns None if either one is text.
+    pub fn cmp_numeric(&self, other: &Answer) -> Option<Ordering> {
+        match (self, other) {
+            (Answer::Unsigned(a), Answer::Unsigned(b)) => Some(a.cmp(b)),
+            (Answer::Signed(a), Answer::Signed(b)) => Some(a.cmp(b)),
+            _ => Some(self.to_bigint()?.cmp(&other.to_bigint()?)),
+        }
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Answer::Text(a), Answer::Text(b)) => a == b,
+            (Answer::Text(_), _) | (_, Answer::Text(_)) => false,
+            _ => self.cmp_numeric(other) == Some(Ordering::Equal),
+        }
+    }
+}
+
+impl Eq for Answer {}
+
+impl PartialEq<str> for Answer {
+    fn eq(&self, other: &str) -> bool {
+        match self {
+            Answer::Text(s) => s == other,
+            _ => *self == Answer::parse(other),
+        }
+    }
+}
+
+impl PartialEq<&str> for Answer {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Unsigned(n) => write!(f, "{}", n),
+            Answer::Signed(n) => write!(f, "{}", n),
+            Answer::Big(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+// Numbers that fit in 64 bits serialize as numbers. Big integers serialize as
+// strings since most JSON readers would lose precision.
+impl Serialize for Answer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Answer::Unsigned(n) => serializer.serialize_u64(*n),
+            Answer::Signed(n) => serializer.serialize_i64(*n),
+            Answer::Big(n) => serializer.collect_str(n),
+            Answer::Text(s) => serializer.serialize_str(s),
+        }
+    }
+}
+
+macro_rules! impl_from {
+    ($variant:ident: $($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::$variant(n.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_from!(Unsigned: u8, u16, u32, u64);
+impl_from!(Signed: i8, i16, i32, i64);
+impl_from!(Big: u128, i128, BigInt, BigUint);
+impl_from!(Text: String, &str);
+
+impl From<usize> for Answer {
+    fn from(n: usize) -> Self {
+        Answer::Unsigned(n as u64)
+    }
+}
+
+impl From<isize> for Answer {
+    fn from(n: isize) -> Self {
+        Answer::Signed(n as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_test() {
+        assert!(matches!(Answer::parse("42"), Answer::Unsigned(42)));
+        assert!(matches!(Answer::parse(" -7\n"), Answer::Signed(-7)));
+        assert!(matches!(
+            Answer::parse("123456789012345678901234567890"),
+            Answer::Big(_)
+        ));
+        assert!(matches!(Answer::parse("ABC"), Answer::Text(_)));
+    }
+
+    #[test]
+    fn eq_test() {
+        assert_eq!(Answer::from(5u64), Answer::from(5i32));
+        assert_eq!(Answer::from(5u64), Answer::from(5u128));
+        assert_ne!(Answer::from(5u64), Answer::from(-5i32));
+        assert_ne!(Answer::from(5u64), Answer::from("5"));
+        assert_eq!(Answer::from(5u64), "5");
+        assert_eq!(Answer::from("Merry Christmas"), "Merry Christmas");
+    }
+
+    #[test]
+    fn cmp_test() {
+        let big = Answer::parse("100000000000000000000");
+        assert_eq!(
+            Answer::from(-1i64).cmp_numeric(&Answer::from(1u64)),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            big.cmp_numeric(&Answer::from(u64::MAX)),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(big.cmp_numeric(&Answer::from("x")), None);
+    }
+}
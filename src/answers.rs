@@ -0,0 +1,152 @@
+use std::{cmp::Ordering, collections::HashMap, path::Path};
+
+use anyhow::{Context, Result};
+
+use crate::{
+    answer::Answer,
+    report::{Failure, Report, Status},
+    solutions::Solution,
+};
+
+pub const DEFAULT_DIR: &str = "puzzle-answers";
+
+// Known-correct answers, stored next to the puzzle inputs as
+// `puzzle-answers/dayNN.txt`. The first line of each file is the answer to
+// problem 1 and the second line is the answer to problem 2. A blank or absent
+// line means the answer is not known yet.
+#[derive(Clone, Debug, Default)]
+pub struct Answers {
+    answers: HashMap<(usize, usize), Answer>,
+}
+
+impl Answers {
+    pub fn load<'a>(
+        dir: &Path,
+        solutions: impl IntoIterator<Item = (usize, &'a Solution)>,
+    ) -> Result<Self> {
+        let mut answers = HashMap::new();
+
+        for (day, solution) in solutions {
+            let path = dir.join(format!("{}.txt", solution.day));
+            let contents = match std::fs::read_to_string(&path) {
+                Ok(x) => x,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => {
+                    return Err(e).with_context(|| format!("failed to read {}", path.display()));
+                }
+            };
+
+            for (problem, line) in (1..=2).zip(contents.lines()) {
+                let line = line.trim();
+                if !line.is_empty() {
+                    answers.insert((day, problem), Answer::parse(line));
+                }
+            }
+        }
+
+        Ok(Self { answers })
+    }
+
+    pub fn get(&self, day: usize, problem: usize) -> Option<&Answer> {
+        self.answers.get(&(day, problem))
+    }
+
+    pub fn check<'a>(&'a self, report: &'a Report) -> Check<'a> {
+        match (&report.outcome, self.get(report.day, report.problem)) {
+            (Ok(solved), Some(expected)) if solved.answer == *expected => Check::Pass,
+            (Ok(solved), Some(expected)) => Check::Fail {
+                expected,
+                got: &solved.answer,
+            },
+            (Ok(solved), None) => Check::NoAnswer(&solved.answer),
+            (Err(failure), _) => Check::Failed(failure),
+        }
+    }
+}
+
+// How a report compares with the known answer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Check<'a> {
+    Pass,
+    Fail {
+        expected: &'a Answer,
+        got: &'a Answer,
+    },
+    // Solved, but the answer isn't known yet.
+    NoAnswer(&'a Answer),
+    Failed(&'a Failure),
+}
+
+impl Check<'_> {
+    // Whether this should fail a verification. Days without an input can't be
+    // checked, but aren't wrong.
+    pub fn is_failure(&self) -> bool {
+        match self {
+            Check::Pass | Check::NoAnswer(_) => false,
+            Check::Fail { .. } => true,
+            Check::Failed(failure) => failure.status != Status::Missing,
+        }
+    }
+}
+
+impl std::fmt::Display for Check<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Check::Pass => write!(f, "PASS"),
+            Check::Fail { expected, got } => {
+                let hint = match got.cmp_numeric(expected) {
+                    Some(Ordering::Greater) => ", too high",
+                    Some(Ordering::Less) => ", too low",
+                    _ => "",
+                };
+                write!(f, "FAIL (expected {}, got {}{})", expected, got, hint)
+            }
+            Check::NoAnswer(got) => write!(f, "NO_ANSWER (got {})", got),
+            Check::Failed(failure) => write!(f, "{} ({})", failure.status, failure),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    use crate::report::Solved;
+
+    #[test]
+    fn check_test() {
+        let answers = Answers {
+            answers: HashMap::from([((1, 1), Answer::from(10u64)), ((1, 2), Answer::from(5u64))]),
+        };
+        let solved = |problem, answer: u64| {
+            let solved = Solved {
+                answer: answer.into(),
+                parse: Duration::ZERO,
+                solve: Duration::ZERO,
+                alloc: None,
+            };
+            Report::new(1, problem, Ok(solved))
+        };
+
+        let pass = solved(1, 10);
+        assert_eq!(answers.check(&pass), Check::Pass);
+        let high = solved(2, 7);
+        assert_eq!(
+            answers.check(&high).to_string(),
+            "FAIL (expected 5, got 7, too high)"
+        );
+        assert!(answers.check(&high).is_failure());
+        let unknown = Report::new(2, 1, solved(1, 3).outcome);
+        assert_eq!(answers.check(&unknown).to_string(), "NO_ANSWER (got 3)");
+        assert!(!answers.check(&unknown).is_failure());
+
+        let missing = Report::new(
+            1,
+            1,
+            Err(anyhow::anyhow!(crate::inputs::Missing("day01.txt".into())).into()),
+        );
+        assert!(!answers.check(&missing).is_failure());
+    }
+}
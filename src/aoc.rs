@@ -0,0 +1,382 @@
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result, anyhow, bail};
+
+use adventofcode2025::answer::Answer;
+
+use crate::guesses::Verdict;
+
+pub const YEAR: usize = 2025;
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+pub const BASE_URL_ENV: &str = "AOC_BASE_URL";
+pub const SESSION_ENV: &str = "AOC_SESSION";
+pub const DEFAULT_SESSION_FILE: &str = ".aoc-session";
+pub const DEFAULT_STAMP_FILE: &str = ".aoc-last-request";
+
+const USER_AGENT: &str = concat!(
+    env!("CARGO_PKG_NAME"),
+    "/",
+    env!("CARGO_PKG_VERSION"),
+    " (personal puzzle runner)"
+);
+
+// Where to reach the Advent of Code site and how to log in to it.
+#[derive(clap::Args)]
+pub struct Config {
+    /// Session cookie for adventofcode.com. Read from --session-file when not
+    /// set.
+    #[arg(long, env = SESSION_ENV, hide_env_values = true)]
+    session: Option<String>,
+    #[arg(long, default_value = DEFAULT_SESSION_FILE)]
+    session_file: PathBuf,
+    /// Server to talk to instead of adventofcode.com
+    #[arg(long, env = BASE_URL_ENV, default_value = DEFAULT_BASE_URL)]
+    base_url: String,
+    /// Minimum time between two requests to the server, e.g. "5s"
+    #[arg(long, default_value = "5s", value_parser = humantime::parse_duration)]
+    min_interval: Duration,
+    /// File remembering when the last request was sent
+    #[arg(long, default_value = DEFAULT_STAMP_FILE)]
+    stamp_file: PathBuf,
+}
+
+impl Config {
+    pub fn client(&self) -> Result<Client> {
+        let session = match &self.session {
+            Some(x) => x.clone(),
+            None => std::fs::read_to_string(&self.session_file).with_context(|| {
+                format!(
+                    "no session token: set {} or write it to {}",
+                    SESSION_ENV,
+                    self.session_file.display()
+                )
+            })?,
+        };
+
+        let limit = RateLimit {
+            path: self.stamp_file.clone(),
+            interval: self.min_interval,
+        };
+        Ok(Client::new(&self.base_url, session.trim(), limit))
+    }
+}
+
+// A logged in connection to the Advent of Code site.
+pub struct Client {
+    base_url: String,
+    session: String,
+    limit: RateLimit,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: &str, limit: RateLimit) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.to_string(),
+            limit,
+            agent: ureq::AgentBuilder::new()
+                .user_agent(USER_AGENT)
+                .timeout(Duration::from_secs(30))
+                .build(),
+        }
+    }
+
+    fn url(&self, day: usize, rest: &str) -> String {
+        format!("{}/{}/day/{}{}", self.base_url, YEAR, day, rest)
+    }
+
+    fn cookie(&self) -> String {
+        format!("session={}", self.session)
+    }
+
+    pub fn input(&self, day: usize) -> Result<String> {
+        self.limit.wait()?;
+        let res = self
+            .agent
+            .get(&self.url(day, "/input"))
+            .set("Cookie", &self.cookie())
+            .call();
+
+        let body = read_response(day, res)?;
+        if body.is_empty() {
+            bail!("server returned an empty input");
+        }
+        Ok(body)
+    }
+
+    pub fn submit(&self, day: usize, part: usize, answer: &Answer) -> Result<Verdict> {
+        self.limit.wait()?;
+        let res = self
+            .agent
+            .post(&self.url(day, "/answer"))
+            .set("Cookie", &self.cookie())
+            .send_form(&[
+                ("level", &part.to_string()),
+                ("answer", &answer.to_string()),
+            ]);
+
+        parse_verdict(&read_response(day, res)?)
+    }
+}
+
+fn read_response(day: usize, res: Result<ureq::Response, ureq::Error>) -> Result<String> {
+    match res {
+        Ok(res) => res.into_string().context("failed to read response"),
+        Err(ureq::Error::Status(404, _)) => bail!("day {} is not available yet", day),
+        Err(ureq::Error::Status(400, _)) => bail!("the session token was rejected"),
+        Err(ureq::Error::Status(code, res)) => {
+            let body = res.into_string().unwrap_or_default();
+            bail!("server returned {}: {}", code, body.trim())
+        }
+        Err(e) => Err(anyhow!(e).context("request failed")),
+    }
+}
+
+// Reads the verdict out of the page returned for a submitted answer.
+fn parse_verdict(body: &str) -> Result<Verdict> {
+    if body.contains("That's the right answer") {
+        Ok(Verdict::Correct)
+    } else if body.contains("your answer is too high") {
+        Ok(Verdict::TooHigh)
+    } else if body.contains("your answer is too low") {
+        Ok(Verdict::TooLow)
+    } else if body.contains("That's not the right answer") {
+        Ok(Verdict::Wrong)
+    } else if body.contains("You gave an answer too recently") {
+        // "... You have 1m 3s left to wait."
+        let wait = body
+            .split_once("You have ")
+            .and_then(|(_, rest)| rest.split_once(" left to wait"))
+            .and_then(|(x, _)| humantime::parse_duration(x).ok());
+        Ok(Verdict::Wait(wait))
+    } else if body.contains("You don't seem to be solving the right level") {
+        bail!("that part is already solved or not unlocked yet")
+    } else {
+        bail!("unrecognized response from server")
+    }
+}
+
+// Spaces requests at least `interval` apart, across runs, by remembering in
+// `path` when the last one was sent.
+pub struct RateLimit {
+    pub path: PathBuf,
+    pub interval: Duration,
+}
+
+impl RateLimit {
+    pub fn wait(&self) -> Result<()> {
+        if let Some(last) = self.last()? {
+            let next = last + self.interval;
+            if let Ok(wait) = next.duration_since(SystemTime::now()) {
+                println!("waiting {:?} before the next request", wait);
+                std::thread::sleep(wait);
+            }
+        }
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+        std::fs::write(&self.path, now.as_nanos().to_string())
+            .with_context(|| format!("failed to write {}", self.path.display()))
+    }
+
+    fn last(&self) -> Result<Option<SystemTime>> {
+        let contents = match std::fs::read_to_string(&self.path) {
+            Ok(x) => x,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(e).with_context(|| format!("failed to read {}", self.path.display()));
+            }
+        };
+        let nanos: u64 = contents
+            .trim()
+            .parse()
+            .with_context(|| format!("bad timestamp in {}", self.path.display()))?;
+        Ok(Some(UNIX_EPOCH + Duration::from_nanos(nanos)))
+    }
+}
+
+// Downloads the input for `day` into `inputs_dir`, unless it is already there.
+pub fn fetch(client: &Client, inputs_dir: &Path, day: usize) -> Result<PathBuf> {
+    let path = inputs_dir.join(format!("day{:02}.txt", day));
+    // `new-day` leaves an empty placeholder, which doesn't count.
+    if std::fs::metadata(&path).is_ok_and(|x| x.len() > 0) {
+        println!("{} already exists", path.display());
+        return Ok(path);
+    }
+
+    let input = client.input(day)?;
+
+    std::fs::create_dir_all(inputs_dir)
+        .with_context(|| format!("failed to create {}", inputs_dir.display()))?;
+    // Write to a temporary file first so an interrupted write doesn't leave a
+    // truncated input that would never be fetched again.
+    let tmp = path.with_extension("txt.tmp");
+    std::fs::write(&tmp, input).with_context(|| format!("failed to write {}", tmp.display()))?;
+    std::fs::rename(&tmp, &path).with_context(|| format!("failed to write {}", path.display()))?;
+
+    println!("wrote {}", path.display());
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        sync::{Arc, Mutex},
+        time::Instant,
+    };
+
+    use super::*;
+
+    // A stub HTTP server that answers the given requests in order, then stops.
+    // Returns its base URL and the requests it received.
+    fn serve(responses: Vec<(u16, &'static str)>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let received = requests.clone();
+        std::thread::spawn(move || {
+            for (code, body) in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+
+                let mut request = String::new();
+                let mut len = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some(x) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                        len = x.trim().parse().unwrap();
+                    }
+                    request.push_str(&line);
+                    if line == "\r\n" {
+                        break;
+                    }
+                }
+                let mut content = vec![0; len];
+                reader.read_exact(&mut content).unwrap();
+                request.push_str(&String::from_utf8(content).unwrap());
+                received.lock().unwrap().push(request);
+
+                write!(
+                    reader.get_mut(),
+                    "HTTP/1.1 {} X\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    code,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        });
+
+        (url, requests)
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn client(url: &str, dir: &Path) -> Client {
+        let limit = RateLimit {
+            path: dir.join("stamp"),
+            interval: Duration::ZERO,
+        };
+        Client::new(url, "abc", limit)
+    }
+
+    #[test]
+    fn fetch_test() {
+        let dir = temp_dir("fetch");
+        let (url, requests) = serve(vec![(200, "1\n2\n")]);
+        let client = client(&url, &dir);
+
+        let path = fetch(&client, &dir, 3).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "1\n2\n");
+
+        let requests = requests.lock().unwrap().clone();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].starts_with("GET /2025/day/3/input HTTP/1.1\r\n"));
+        assert!(requests[0].contains("\r\nCookie: session=abc\r\n"));
+
+        // The stub only answers once, so this would fail if it hit the server.
+        assert_eq!(fetch(&client, &dir, 3).unwrap(), path);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn fetch_error_test() {
+        let dir = temp_dir("fetch-error");
+        let (url, _) = serve(vec![(404, "Not Found"), (500, "oops")]);
+        let client = client(&url, &dir);
+
+        let err = fetch(&client, &dir, 4).unwrap_err();
+        assert_eq!(err.to_string(), "day 4 is not available yet");
+        let err = fetch(&client, &dir, 4).unwrap_err();
+        assert_eq!(err.to_string(), "server returned 500: oops");
+        assert!(!dir.join("day04.txt").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn submit_test() {
+        let dir = temp_dir("submit");
+        let (url, requests) = serve(vec![
+            (
+                200,
+                "<p>That's not the right answer; your answer is too low.</p>",
+            ),
+            (
+                200,
+                "<p>You gave an answer too recently. You have 1m 3s left to wait.</p>",
+            ),
+            (
+                200,
+                "<p>That's the right answer! You are one gold star closer.</p>",
+            ),
+            (200, "<p>You don't seem to be solving the right level.</p>"),
+        ]);
+        let client = client(&url, &dir);
+
+        let answer = Answer::from(42u64);
+        assert_eq!(client.submit(5, 2, &answer).unwrap(), Verdict::TooLow);
+        assert_eq!(
+            client.submit(5, 2, &answer).unwrap(),
+            Verdict::Wait(Some(Duration::from_secs(63)))
+        );
+        assert_eq!(client.submit(5, 2, &answer).unwrap(), Verdict::Correct);
+        assert!(client.submit(5, 2, &answer).is_err());
+
+        let requests = requests.lock().unwrap().clone();
+        assert!(requests[0].starts_with("POST /2025/day/5/answer HTTP/1.1\r\n"));
+        assert!(requests[0].ends_with("\r\n\r\nlevel=2&answer=42"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn rate_limit_test() {
+        let dir = temp_dir("rate-limit");
+        let limit = RateLimit {
+            path: dir.join("stamp"),
+            interval: Duration::from_millis(200),
+        };
+
+        let start = Instant::now();
+        limit.wait().unwrap();
+        assert!(start.elapsed() < Duration::from_millis(200));
+        limit.wait().unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(200));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,335 @@
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+
+use crate::{
+    alloc::Usage,
+    answer::Answer,
+    params::Params,
+    report::Failure,
+    runner,
+    solutions::{DEFAULT_VARIANT, Solution},
+};
+
+// Never stop a time-budgeted run with fewer samples than this, otherwise the
+// confidence interval is meaningless.
+const MIN_BUDGET_SAMPLES: usize = 5;
+
+// z-score for a two-sided 95% confidence interval.
+const Z_95: f64 = 1.96;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Options {
+    pub warmup: usize,
+    pub samples: usize,
+    // When set, keep sampling until the 95% confidence interval of the mean is
+    // within `precision` of the mean or the budget runs out.
+    pub budget: Option<Duration>,
+    pub precision: f64,
+}
+
+// Runs `f` repeatedly according to `opts`. `f` returns the duration of the
+// part of the call that should be measured.
+pub fn sample(opts: &Options, mut f: impl FnMut() -> Result<Duration>) -> Result<Stats> {
+    for _ in 0..opts.warmup {
+        f()?;
+    }
+
+    let mut samples = Vec::with_capacity(opts.samples);
+
+    match opts.budget {
+        None => {
+            for _ in 0..opts.samples.max(1) {
+                samples.push(f()?);
+            }
+        }
+        Some(budget) => {
+            let start = Instant::now();
+            loop {
+                samples.push(f()?);
+
+                if samples.len() >= MIN_BUDGET_SAMPLES {
+                    let stats = Stats::new(&samples);
+                    if stats.relative_ci() <= opts.precision || start.elapsed() >= budget {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(Stats::new(&samples))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stats {
+    pub samples: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub std_dev: Duration,
+    // Half-width of the 95% confidence interval of the mean.
+    pub ci: Duration,
+    // Samples outside the Tukey fences (1.5 IQR beyond the quartiles).
+    pub outliers: usize,
+}
+
+impl Stats {
+    pub fn new(samples: &[Duration]) -> Self {
+        assert!(!samples.is_empty(), "no samples");
+
+        let mut sorted: Vec<f64> = samples.iter().map(|d| d.as_secs_f64()).collect();
+        sorted.sort_unstable_by(|a, b| a.total_cmp(b));
+
+        let n = sorted.len() as f64;
+        let mean = sorted.iter().sum::<f64>() / n;
+        let variance = if sorted.len() > 1 {
+            sorted.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0)
+        } else {
+            0.0
+        };
+        let std_dev = variance.sqrt();
+
+        let q1 = quantile(&sorted, 0.25);
+        let q3 = quantile(&sorted, 0.75);
+        let iqr = q3 - q1;
+        let (low, high) = (q1 - 1.5 * iqr, q3 + 1.5 * iqr);
+        let outliers = sorted.iter().filter(|&&x| x < low || x > high).count();
+
+        Self {
+            samples: sorted.len(),
+            min: Duration::from_secs_f64(sorted[0]),
+            median: Duration::from_secs_f64(quantile(&sorted, 0.5)),
+            mean: Duration::from_secs_f64(mean),
+            std_dev: Duration::from_secs_f64(std_dev),
+            ci: Duration::from_secs_f64(Z_95 * std_dev / n.sqrt()),
+            outliers,
+        }
+    }
+
+    // The confidence interval half-width as a fraction of the mean.
+    pub fn relative_ci(&self) -> f64 {
+        if self.mean.is_zero() {
+            return 0.0;
+        }
+
+        self.ci.as_secs_f64() / self.mean.as_secs_f64()
+    }
+}
+
+impl std::fmt::Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "min {:?}  median {:?}  mean {:?} ± {:?}  sd {:?}  (n={}, {} outliers)",
+            self.min, self.median, self.mean, self.ci, self.std_dev, self.samples, self.outliers
+        )
+    }
+}
+
+// One line of a `bench_day`: the parse, or one variant of a problem.
+#[derive(Clone, Debug)]
+pub struct Row {
+    // None for the parse.
+    pub problem: Option<usize>,
+    // None for the parse and for problems with only the default variant.
+    pub variant: Option<&'static str>,
+    pub outcome: Outcome,
+}
+
+#[derive(Clone, Debug)]
+pub enum Outcome {
+    Timed {
+        stats: Stats,
+        // Allocations don't vary between samples, so these are the last one's.
+        alloc: Option<Usage>,
+        // The median as a multiple of the default variant's.
+        relative: Option<f64>,
+    },
+    // A variant that disagrees with the default, which isn't timed.
+    Mismatch {
+        got: Answer,
+        expected: Answer,
+    },
+    Failed(Failure),
+}
+
+// Benchmarks parsing a normalized input and then each variant of each
+// problem on the parsed input, after checking that the variants agree with the
+// default. Each row is passed to `emit` as soon as it is measured. Returns the
+// median parse plus solve time of each problem's default.
+pub fn bench_day(
+    solution: &Solution,
+    input: &str,
+    problems: &[usize],
+    params: &Params,
+    opts: &Options,
+    mut emit: impl FnMut(Row),
+) -> Result<Vec<(usize, Duration)>> {
+    let mut alloc = None;
+    let parse_stats = sample(opts, || {
+        let cost = runner::parse(solution, input)?.1;
+        alloc = cost.alloc;
+        Ok(cost.duration)
+    })?;
+    emit(Row {
+        problem: None,
+        variant: None,
+        outcome: Outcome::Timed {
+            stats: parse_stats,
+            alloc,
+            relative: None,
+        },
+    });
+
+    let (parsed, _) = runner::parse(solution, input)?;
+    let mut medians = Vec::new();
+    for &problem in problems {
+        let variants = (solution.variants)(problem);
+        // The default's answer, which every other variant has to give too.
+        let expected = runner::solve(&*parsed, problem, params).ok();
+        let mut default_median: Option<Duration> = None;
+
+        for variant in variants.iter().copied() {
+            let mut row = |outcome| {
+                emit(Row {
+                    problem: Some(problem),
+                    variant: (variants.len() > 1).then_some(variant),
+                    outcome,
+                })
+            };
+            if let Some((expected, _)) = &expected
+                && variant != DEFAULT_VARIANT
+            {
+                match runner::solve_variant(&*parsed, problem, variant, params) {
+                    Ok((got, _)) if got != *expected => {
+                        row(Outcome::Mismatch {
+                            got,
+                            expected: expected.clone(),
+                        });
+                        continue;
+                    }
+                    Err(e) => {
+                        row(Outcome::Failed(e.into()));
+                        continue;
+                    }
+                    Ok(_) => {}
+                }
+            }
+
+            let mut alloc = None;
+            let stats = sample(opts, || {
+                let cost = runner::solve_variant(&*parsed, problem, variant, params)?.1;
+                alloc = cost.alloc;
+                Ok(cost.duration)
+            });
+            let stats = match stats {
+                Ok(x) => x,
+                Err(e) => {
+                    row(Outcome::Failed(e.into()));
+                    continue;
+                }
+            };
+
+            let relative = match default_median {
+                Some(base) if variant != DEFAULT_VARIANT => {
+                    Some(stats.median.as_secs_f64() / base.as_secs_f64())
+                }
+                _ => None,
+            };
+            row(Outcome::Timed {
+                stats,
+                alloc,
+                relative,
+            });
+            if variant == DEFAULT_VARIANT {
+                default_median = Some(stats.median);
+                medians.push((problem, parse_stats.median + stats.median));
+            }
+        }
+    }
+
+    Ok(medians)
+}
+
+// Linearly interpolated quantile of sorted data.
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let pos = q * (sorted.len() - 1) as f64;
+    let lo = pos.floor() as usize;
+    let hi = pos.ceil() as usize;
+    sorted[lo] + (sorted[hi] - sorted[lo]) * (pos - lo as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(xs: &[u64]) -> Vec<Duration> {
+        xs.iter().map(|&x| Duration::from_millis(x)).collect()
+    }
+
+    #[test]
+    fn stats_test() {
+        let stats = Stats::new(&ms(&[10, 12, 11, 13, 50, 12, 11]));
+        assert_eq!(stats.samples, 7);
+        assert_eq!(stats.min, Duration::from_millis(10));
+        assert_eq!(stats.median, Duration::from_millis(12));
+        assert_eq!(stats.outliers, 1);
+    }
+
+    #[test]
+    fn sample_budget_test() {
+        let opts = Options {
+            warmup: 2,
+            samples: 1000,
+            budget: Some(Duration::from_secs(10)),
+            precision: 0.05,
+        };
+
+        // Identical samples have a zero-width interval, so sampling stops as
+        // soon as the minimum sample count is reached.
+        let mut calls = 0;
+        let stats = sample(&opts, || {
+            calls += 1;
+            Ok(Duration::from_millis(1))
+        })
+        .unwrap();
+
+        assert_eq!(stats.samples, MIN_BUDGET_SAMPLES);
+        assert_eq!(calls, MIN_BUDGET_SAMPLES + 2);
+    }
+
+    #[test]
+    fn bench_day_test() {
+        let opts = Options {
+            warmup: 0,
+            samples: 1,
+            budget: None,
+            precision: 0.0,
+        };
+        let solution = runner::lookup(2).unwrap();
+        let params = Params::resolve(solution.params, &[]).unwrap();
+        let mut rows = Vec::new();
+        let medians = bench_day(solution, "11-22\n", &[1, 2], &params, &opts, |row| {
+            rows.push((row.problem, row.variant, row.outcome))
+        })
+        .unwrap();
+
+        let labels: Vec<_> = rows.iter().map(|(p, v, _)| (*p, *v)).collect();
+        assert_eq!(
+            labels,
+            [
+                (None, None),
+                (Some(1), Some(DEFAULT_VARIANT)),
+                (Some(1), Some("arithmetic")),
+                (Some(2), Some(DEFAULT_VARIANT)),
+                (Some(2), Some("arithmetic")),
+            ]
+        );
+        assert!(
+            rows.iter()
+                .all(|(_, _, x)| matches!(x, Outcome::Timed { .. }))
+        );
+        assert_eq!(medians.iter().map(|x| x.0).collect::<Vec<_>>(), [1, 2]);
+    }
+}
@@ -0,0 +1,263 @@
+use std::path::Path;
+
+use anyhow::{Result, bail};
+
+use crate::{
+    answer::Answer,
+    examples::{self, Example},
+    generate,
+    params::Params,
+    report::Failure,
+    runner,
+    solutions::Solution,
+};
+
+#[derive(Clone, Copy, Debug)]
+pub struct Options {
+    // Seed of the first generated input. Each later one adds one.
+    pub seed: u64,
+    // How many inputs to generate, growing from size 1 up to `size`.
+    pub runs: usize,
+    pub size: usize,
+}
+
+// Where a checked input came from, so that it can be made again.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Source {
+    Example(usize),
+    Generated { seed: u64, size: usize },
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::Example(index) => write!(f, "example {}", index),
+            Source::Generated { seed, size } => write!(f, "gen --seed {} --size {}", seed, size),
+        }
+    }
+}
+
+// An input on which a problem and its oracle disagree, cut down to as few
+// lines as still show it.
+#[derive(Debug)]
+pub struct Mismatch {
+    pub source: Source,
+    pub input: String,
+    pub expected: Answer,
+    pub got: Result<Answer, Failure>,
+}
+
+#[derive(Debug)]
+pub struct Summary {
+    pub agreed: usize,
+    // Inputs the oracle couldn't handle, e.g. because they were too big.
+    pub skipped: usize,
+    pub mismatch: Option<Mismatch>,
+}
+
+enum Verdict {
+    Agree,
+    Skip,
+    Disagree {
+        expected: Answer,
+        got: Result<Answer, Failure>,
+    },
+}
+
+// Crosschecks `problem`, or every problem with an oracle, against the day's
+// examples from `examples_dir` and generated inputs.
+pub fn crosscheck_day(
+    solution: &Solution,
+    problem: Option<usize>,
+    examples_dir: &Path,
+    overrides: &[(String, i64)],
+    opts: &Options,
+) -> Result<Vec<(usize, Summary)>> {
+    let problems: Vec<usize> = match problem {
+        Some(problem) => vec![problem],
+        None => (1..=2).filter(|&p| solution.oracles[p - 1]).collect(),
+    };
+    if problems.is_empty() {
+        bail!("{} has no oracles", solution.day);
+    }
+    let examples = examples::load(examples_dir, solution.day)?;
+
+    problems
+        .into_iter()
+        .map(|problem| {
+            Ok((
+                problem,
+                crosscheck(solution, problem, &examples, overrides, opts)?,
+            ))
+        })
+        .collect()
+}
+
+// Compares `problem` against its oracle on the examples and then on generated
+// inputs, stopping at the first disagreement. Small inputs come first, so the
+// first one found tends to be small already.
+pub fn crosscheck(
+    solution: &Solution,
+    problem: usize,
+    examples: &[Example],
+    overrides: &[(String, i64)],
+    opts: &Options,
+) -> Result<Summary> {
+    if !solution
+        .oracles
+        .get(problem.wrapping_sub(1))
+        .unwrap_or(&false)
+    {
+        bail!("{} has no oracle for problem {}", solution.day, problem);
+    }
+
+    let mut inputs = Vec::new();
+    for example in examples {
+        let mut example_overrides = example.params.clone();
+        example_overrides.extend_from_slice(overrides);
+        let params = Params::resolve(solution.params, &example_overrides)?;
+        inputs.push((
+            Source::Example(example.index),
+            example.input.clone(),
+            params,
+        ));
+    }
+    if solution.generate.is_some() {
+        let params = Params::resolve(solution.params, overrides)?;
+        for i in 0..opts.runs {
+            let seed = opts.seed.wrapping_add(i as u64);
+            let size = 1 + i * opts.size.saturating_sub(1) / opts.runs.max(2).saturating_sub(1);
+            let input = generate::generate(solution, seed, size)?;
+            inputs.push((Source::Generated { seed, size }, input, params.clone()));
+        }
+    }
+
+    let mut summary = Summary {
+        agreed: 0,
+        skipped: 0,
+        mismatch: None,
+    };
+    for (source, input, params) in inputs {
+        match compare(solution, problem, &input, &params) {
+            Verdict::Agree => summary.agreed += 1,
+            Verdict::Skip => summary.skipped += 1,
+            Verdict::Disagree { .. } => {
+                let input = minimize(&input, |x| {
+                    matches!(
+                        compare(solution, problem, x, &params),
+                        Verdict::Disagree { .. }
+                    )
+                });
+                let Verdict::Disagree { expected, got } =
+                    compare(solution, problem, &input, &params)
+                else {
+                    unreachable!("minimize keeps the input failing");
+                };
+                summary.mismatch = Some(Mismatch {
+                    source,
+                    input,
+                    expected,
+                    got,
+                });
+                break;
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+// Checks `problem` against its oracle on `input`, normalized as `run` would.
+fn compare(solution: &Solution, problem: usize, input: &str, params: &Params) -> Verdict {
+    let (input, _) = runner::prepare(solution, input);
+    let Ok((parsed, _)) = runner::parse(solution, &input) else {
+        return Verdict::Skip;
+    };
+    let Ok((expected, _)) = runner::oracle(&*parsed, problem, params) else {
+        return Verdict::Skip;
+    };
+
+    match runner::solve(&*parsed, problem, params) {
+        Ok((got, _)) if got == expected => Verdict::Agree,
+        res => Verdict::Disagree {
+            expected,
+            got: res.map(|(answer, _)| answer).map_err(Failure::from),
+        },
+    }
+}
+
+// Removes lines from `input` for as long as `fails` still holds, trying to
+// remove large blocks first.
+pub fn minimize(input: &str, mut fails: impl FnMut(&str) -> bool) -> String {
+    let join = |lines: &[&str]| lines.iter().map(|x| format!("{}\n", x)).collect::<String>();
+
+    let mut lines: Vec<&str> = input.lines().collect();
+    let mut chunk = lines.len() / 2;
+    while chunk > 0 {
+        let mut start = 0;
+        while start < lines.len() {
+            let end = (start + chunk).min(lines.len());
+            let candidate: Vec<_> = lines[..start]
+                .iter()
+                .chain(&lines[end..])
+                .copied()
+                .collect();
+            if !candidate.is_empty() && fails(&join(&candidate)) {
+                lines = candidate;
+            } else {
+                start = end;
+            }
+        }
+        chunk /= 2;
+    }
+
+    join(&lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::solutions::SOLUTIONS;
+
+    #[test]
+    fn minimize_test() {
+        let input = "a\nb\nc\nd\ne\nf\ng\n";
+        let fails = |x: &str| x.contains('b') && x.contains('f');
+        assert_eq!(minimize(input, fails), "b\nf\n");
+        assert_eq!(minimize("a\n", |_| true), "a\n");
+    }
+
+    #[test]
+    fn crosscheck_test() {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(examples::DEFAULT_DIR);
+        let opts = Options {
+            seed: 0,
+            runs: 20,
+            size: 12,
+        };
+
+        for solution in SOLUTIONS.values().filter(|x| x.oracles.contains(&true)) {
+            for (problem, summary) in crosscheck_day(solution, None, &dir, &[], &opts).unwrap() {
+                assert!(
+                    summary.mismatch.is_none(),
+                    "{} problem {}: {:?}",
+                    solution.day,
+                    problem,
+                    summary.mismatch
+                );
+                assert!(summary.agreed > 0, "{} problem {}", solution.day, problem);
+            }
+        }
+
+        // Inputs are normalized first, like `run` does.
+        let day01 = SOLUTIONS[&1];
+        let params = Params::resolve(day01.params, &[]).unwrap();
+        let verdict = compare(day01, 1, "\u{feff}L68\r\nR32\r\nL5", &params);
+        assert!(matches!(verdict, Verdict::Agree));
+
+        let day02 = SOLUTIONS[&2];
+        assert!(crosscheck(day02, 1, &[], &[], &opts).is_err());
+        assert!(crosscheck_day(day02, None, &dir, &[], &opts).is_err());
+    }
+}
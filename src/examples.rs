@@ -0,0 +1,178 @@
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+
+use crate::{answer::Answer, params};
+
+pub const DEFAULT_DIR: &str = "puzzle-examples";
+
+// An example input from a puzzle description, stored as
+// `puzzle-examples/dayNN-K.txt`. The expected answers are in `dayNN-K.answers`,
+// laid out like the files in `puzzle-answers`: problem 1 on the first line and
+// problem 2 on the second, blank when the example doesn't apply to a problem.
+// Parameters the example needs, like the smaller pair count of day08, are in
+// `dayNN-K.params` as one `name=value` per line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Example {
+    pub index: usize,
+    pub input: String,
+    pub answers: [Option<Answer>; 2],
+    pub params: Vec<(String, i64)>,
+}
+
+impl Example {
+    pub fn answer(&self, problem: usize) -> Option<&Answer> {
+        self.answers.get(problem.wrapping_sub(1))?.as_ref()
+    }
+}
+
+// Loads every example for the day named `day` (e.g. "day01"), in order.
+pub fn load(dir: &Path, day: &str) -> Result<Vec<Example>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(x) => x,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("failed to read {}", dir.display())),
+    };
+
+    let prefix = format!("{}-", day);
+    let mut indexes = Vec::new();
+    for entry in entries {
+        let name = entry?.file_name();
+        let index = name
+            .to_str()
+            .and_then(|x| x.strip_prefix(&prefix))
+            .and_then(|x| x.strip_suffix(".txt"))
+            .and_then(|x| x.parse::<usize>().ok());
+        indexes.extend(index);
+    }
+    indexes.sort_unstable();
+
+    indexes.into_iter().map(|k| load_one(dir, day, k)).collect()
+}
+
+pub fn load_one(dir: &Path, day: &str, index: usize) -> Result<Example> {
+    let path = dir.join(format!("{}-{}.txt", day, index));
+    let input = std::fs::read_to_string(&path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+
+    let path = path.with_extension("answers");
+    let mut answers = [None, None];
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => {
+            for (answer, line) in answers.iter_mut().zip(contents.lines()) {
+                let line = line.trim();
+                if !line.is_empty() {
+                    *answer = Some(Answer::parse(line));
+                }
+            }
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e).with_context(|| format!("failed to read {}", path.display())),
+    }
+
+    let path = path.with_extension("params");
+    let params = match std::fs::read_to_string(&path) {
+        Ok(contents) => contents
+            .lines()
+            .filter(|x| !x.trim().is_empty())
+            .map(params::parse_assignment)
+            .collect::<Result<_>>()
+            .with_context(|| format!("bad parameters in {}", path.display()))?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(e).with_context(|| format!("failed to read {}", path.display())),
+    };
+
+    Ok(Example {
+        index,
+        input,
+        answers,
+        params,
+    })
+}
+
+// Picks example `index` or, when not given, the first example with an expected
+// answer for `problem`.
+pub fn select(dir: &Path, day: &str, problem: usize, index: Option<usize>) -> Result<Example> {
+    if let Some(index) = index {
+        return load_one(dir, day, index);
+    }
+
+    let examples = load(dir, day)?;
+    let Some(first) = examples.first() else {
+        bail!("no examples for {} in {}", day, dir.display());
+    };
+    Ok(examples
+        .iter()
+        .find(|x| x.answer(problem).is_some())
+        .unwrap_or(first)
+        .clone())
+}
+
+// Checks every variant of a day's solution against all of its examples.
+// Called by the tests that `days!` generates for each day.
+#[cfg(test)]
+pub fn check(day: &str) {
+    let solution = crate::solutions::SOLUTIONS
+        .values()
+        .find(|x| x.day == day)
+        .unwrap();
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join(DEFAULT_DIR);
+
+    for example in load(&dir, day).unwrap() {
+        let params = params::Params::resolve(solution.params, &example.params).unwrap();
+        // Normalized like `run --example` does.
+        let (input, _) = crate::runner::prepare(solution, &example.input);
+        let parsed = (solution.parse)(&input).unwrap();
+        for problem in 1..=2 {
+            let Some(expected) = example.answer(problem) else {
+                continue;
+            };
+            for variant in (solution.variants)(problem) {
+                let answer = parsed.variant(problem, variant, &params).unwrap();
+                assert_eq!(
+                    &answer, expected,
+                    "{} problem {} ({}) on example {}",
+                    day, problem, variant, example.index
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_test() {
+        let dir = std::env::temp_dir().join(format!("aoc-examples-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("day03-2.txt"), "b").unwrap();
+        std::fs::write(dir.join("day03-2.answers"), "\n7\n").unwrap();
+        std::fs::write(dir.join("day03-1.txt"), "a").unwrap();
+        std::fs::write(dir.join("day03-1.answers"), "5\n").unwrap();
+        std::fs::write(dir.join("day03-1.params"), "n=10\n\nk=-1\n").unwrap();
+        std::fs::write(dir.join("day04-1.txt"), "c").unwrap();
+
+        let examples = load(&dir, "day03").unwrap();
+        assert_eq!(examples.len(), 2);
+        assert_eq!(examples[0].input, "a");
+        assert_eq!(examples[0].answers, [Some(5u64.into()), None]);
+        assert_eq!(examples[1].answers, [None, Some(7u64.into())]);
+        assert_eq!(
+            examples[0].params,
+            [("n".to_string(), 10), ("k".to_string(), -1)]
+        );
+        assert!(examples[1].params.is_empty());
+
+        assert_eq!(select(&dir, "day03", 2, None).unwrap().index, 2);
+        assert_eq!(select(&dir, "day03", 1, Some(2)).unwrap().index, 2);
+        assert_eq!(
+            select(&dir, "day04", 2, None).unwrap().answers,
+            [None, None]
+        );
+        assert!(select(&dir, "day05", 1, None).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
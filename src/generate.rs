@@ -0,0 +1,47 @@
+use anyhow::{Result, bail};
+use rand::{SeedableRng, rngs::StdRng};
+
+use crate::solutions::Solution;
+
+// Writes a random input in a day's format. What `size` counts depends on the
+// day, e.g. rotations for day01 or the side of the grid for day04.
+pub type GenerateFn = fn(&mut StdRng, usize) -> String;
+
+// Generates an input for `solution`. The same seed and size always give the
+// same input.
+pub fn generate(solution: &Solution, seed: u64, size: usize) -> Result<String> {
+    let Some(generate) = solution.generate else {
+        bail!("{} has no input generator", solution.day);
+    };
+    Ok(generate(&mut StdRng::seed_from_u64(seed), size))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::solutions::SOLUTIONS;
+
+    #[test]
+    fn generate_test() {
+        let generated: Vec<_> = SOLUTIONS
+            .values()
+            .filter(|x| x.generate.is_some())
+            .collect();
+        assert!(generated.len() >= 8);
+
+        for solution in generated {
+            for size in [1, 2, 10, 50] {
+                let input = generate(solution, 7, size).unwrap();
+                assert_eq!(input, generate(solution, 7, size).unwrap());
+                if let Err(e) = (solution.parse)(&input) {
+                    panic!("{} size {}: {:#}\n{}", solution.day, size, e, input);
+                }
+            }
+            assert_ne!(
+                generate(solution, 1, 50).unwrap(),
+                generate(solution, 2, 50).unwrap()
+            );
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use std::{
+    cmp::Ordering,
+    io::Write,
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result, anyhow, bail};
+
+use adventofcode2025::answer::Answer;
+
+pub const DEFAULT_PATH: &str = "puzzle-answers/guesses.tsv";
+
+// What the server said about a submitted answer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    Wrong,
+    // Submitted too soon after a previous guess. The guess was not checked.
+    Wait(Option<Duration>),
+}
+
+impl Verdict {
+    fn name(&self) -> &'static str {
+        match self {
+            Verdict::Correct => "CORRECT",
+            Verdict::TooHigh => "TOO_HIGH",
+            Verdict::TooLow => "TOO_LOW",
+            Verdict::Wrong => "WRONG",
+            Verdict::Wait(_) => "WAIT",
+        }
+    }
+
+    fn from_name(s: &str) -> Option<Self> {
+        Some(match s {
+            "CORRECT" => Verdict::Correct,
+            "TOO_HIGH" => Verdict::TooHigh,
+            "TOO_LOW" => Verdict::TooLow,
+            "WRONG" => Verdict::Wrong,
+            "WAIT" => Verdict::Wait(None),
+            _ => return None,
+        })
+    }
+}
+
+impl std::fmt::Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::TooHigh => write!(f, "wrong, too high"),
+            Verdict::TooLow => write!(f, "wrong, too low"),
+            Verdict::Wrong => write!(f, "wrong"),
+            Verdict::Wait(Some(x)) => {
+                write!(
+                    f,
+                    "not checked, wait {} first",
+                    humantime::format_duration(*x)
+                )
+            }
+            Verdict::Wait(None) => write!(f, "not checked, submitted too soon"),
+        }
+    }
+}
+
+// A submitted answer. Stored as one tab separated line per guess:
+//
+//   timestamp  day  part  verdict  answer
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Guess {
+    pub timestamp: u64,
+    pub day: usize,
+    pub part: usize,
+    pub verdict: Verdict,
+    pub answer: Answer,
+}
+
+impl Guess {
+    pub fn new(day: usize, part: usize, answer: Answer, verdict: Verdict) -> Result<Self> {
+        Ok(Self {
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+            day,
+            part,
+            verdict,
+            answer,
+        })
+    }
+
+    fn format(&self) -> String {
+        format!(
+            "{}\t{}\t{}\t{}\t{}\n",
+            self.timestamp,
+            self.day,
+            self.part,
+            self.verdict.name(),
+            self.answer
+        )
+    }
+}
+
+// Every answer submitted so far.
+#[derive(Clone, Debug, Default)]
+pub struct Guesses {
+    guesses: Vec<Guess>,
+}
+
+impl Guesses {
+    pub fn load(path: &Path) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(x) => parse(&x),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e).with_context(|| format!("failed to read {}", path.display())),
+        }
+    }
+
+    pub fn append(&mut self, path: &Path, guess: Guess) -> Result<()> {
+        let mut f = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("failed to open {}", path.display()))?;
+        f.write_all(guess.format().as_bytes())
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        self.guesses.push(guess);
+        Ok(())
+    }
+
+    // Fails if `answer` can be ruled out from earlier guesses, so it isn't
+    // worth submitting.
+    pub fn check(&self, day: usize, part: usize, answer: &Answer) -> Result<()> {
+        for g in self
+            .guesses
+            .iter()
+            .filter(|g| g.day == day && g.part == part)
+        {
+            let ord = answer.cmp_numeric(&g.answer);
+            match g.verdict {
+                Verdict::Correct if g.answer == *answer => bail!("{} was already accepted", answer),
+                Verdict::Correct => bail!("already solved with {}", g.answer),
+                Verdict::Wait(_) => {}
+                _ if g.answer == *answer => bail!("{} was already guessed ({})", answer, g.verdict),
+                Verdict::TooHigh if ord == Some(Ordering::Greater) => {
+                    bail!("{} is above {}, which was too high", answer, g.answer)
+                }
+                Verdict::TooLow if ord == Some(Ordering::Less) => {
+                    bail!("{} is below {}, which was too low", answer, g.answer)
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse(contents: &str) -> Result<Guesses> {
+    let mut guesses = Vec::new();
+
+    for (i, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let bad_line = || anyhow!("guesses line {}: malformed", i + 1);
+        let [timestamp, day, part, verdict, answer] = line.splitn(5, '\t').collect::<Vec<_>>()[..]
+        else {
+            bail!("guesses line {}: expected 5 fields", i + 1);
+        };
+        guesses.push(Guess {
+            timestamp: timestamp.parse().map_err(|_| bad_line())?,
+            day: day.parse().map_err(|_| bad_line())?,
+            part: part.parse().map_err(|_| bad_line())?,
+            verdict: Verdict::from_name(verdict).ok_or_else(bad_line)?,
+            answer: Answer::parse(answer),
+        });
+    }
+
+    Ok(Guesses { guesses })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_test() {
+        let guesses = [
+            Guess::new(1, 2, 5u64.into(), Verdict::TooLow).unwrap(),
+            Guess::new(12, 2, "Merry Christmas".into(), Verdict::Correct).unwrap(),
+        ];
+        let contents: String = guesses.iter().map(|g| g.format()).collect();
+        assert_eq!(parse(&contents).unwrap().guesses, guesses);
+    }
+
+    #[test]
+    fn check_test() {
+        let mut guesses = Guesses::default();
+        for (answer, verdict) in [
+            (100u64, Verdict::TooHigh),
+            (10, Verdict::TooLow),
+            (50, Verdict::Wrong),
+            (60, Verdict::Wait(None)),
+        ] {
+            guesses
+                .guesses
+                .push(Guess::new(1, 1, answer.into(), verdict).unwrap());
+        }
+
+        assert!(guesses.check(1, 1, &20u64.into()).is_ok());
+        assert!(guesses.check(1, 1, &60u64.into()).is_ok());
+        assert!(guesses.check(1, 2, &50u64.into()).is_ok());
+        for bad in [100u64, 101, 10, 9, 50] {
+            assert!(guesses.check(1, 1, &bad.into()).is_err(), "{}", bad);
+        }
+
+        guesses
+            .guesses
+            .push(Guess::new(1, 1, 42u64.into(), Verdict::Correct).unwrap());
+        let err = guesses.check(1, 1, &20u64.into()).unwrap_err();
+        assert_eq!(err.to_string(), "already solved with 42");
+    }
+}
@@ -0,0 +1,328 @@
+use std::{
+    collections::BTreeMap,
+    io::Write,
+    path::Path,
+    process::Command,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result, anyhow, bail};
+
+pub const DEFAULT_PATH: &str = "bench-history.tsv";
+
+// One recorded invocation of `run-all` or `bench`. Each run is stored as one
+// tab separated line per problem:
+//
+//   id  timestamp  revision  label  source  day  problem  nanos
+//
+// Lines that share an id belong to the same run. Files written before runs had
+// ids have no id field, and their lines are grouped by timestamp, revision,
+// label and source instead.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Run {
+    // Random, so that runs started in the same second stay apart. Empty for
+    // runs read from an old file.
+    pub id: String,
+    pub timestamp: u64,
+    pub revision: String,
+    pub label: String,
+    pub source: String,
+    pub times: BTreeMap<(usize, usize), Duration>,
+}
+
+impl Run {
+    pub fn new(source: &str, label: Option<&str>) -> Result<Self> {
+        let label = match label {
+            Some(label) => parse_label(label)?,
+            None => "-".to_string(),
+        };
+
+        Ok(Self {
+            id: format!("{:016x}", rand::random::<u64>()),
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+            revision: git_revision(),
+            label,
+            source: source.to_string(),
+            times: BTreeMap::new(),
+        })
+    }
+
+    fn key(&self) -> (&str, u64, &str, &str, &str) {
+        (
+            &self.id,
+            self.timestamp,
+            &self.revision,
+            &self.label,
+            &self.source,
+        )
+    }
+
+    // True if `name` refers to this run, either by label or by a prefix of its
+    // git revision.
+    pub fn matches(&self, name: &str) -> bool {
+        self.label == name || self.revision.starts_with(name)
+    }
+
+    pub fn describe(&self) -> String {
+        let time = UNIX_EPOCH + Duration::from_secs(self.timestamp);
+        let mut ret = format!(
+            "{} @ {} ({})",
+            self.source,
+            self.revision,
+            humantime::format_rfc3339_seconds(time)
+        );
+        if self.label != "-" {
+            ret += &format!(" [{}]", self.label);
+        }
+        ret
+    }
+
+    fn format(&self) -> String {
+        self.times
+            .iter()
+            .map(|((day, problem), duration)| {
+                format!(
+                    "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+                    self.id,
+                    self.timestamp,
+                    self.revision,
+                    self.label,
+                    self.source,
+                    day,
+                    problem,
+                    duration.as_nanos()
+                )
+            })
+            .collect()
+    }
+}
+
+// Checks a label given on the command line. Labels are stored as a tab
+// separated field, with "-" for no label.
+pub fn parse_label(label: &str) -> Result<String> {
+    if label == "-" {
+        bail!("\"-\" is kept for runs without a label");
+    }
+    if label.is_empty() || label.contains(char::is_whitespace) {
+        bail!("label must be non-empty and contain no whitespace");
+    }
+    Ok(label.to_string())
+}
+
+pub fn append(path: &Path, run: &Run) -> Result<()> {
+    let mut f = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("failed to open {}", path.display()))?;
+    f.write_all(run.format().as_bytes())
+        .with_context(|| format!("failed to write {}", path.display()))?;
+    Ok(())
+}
+
+pub fn load(path: &Path) -> Result<Vec<Run>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    parse(&contents)
+}
+
+fn parse(contents: &str) -> Result<Vec<Run>> {
+    let mut runs: Vec<Run> = Vec::new();
+
+    for (i, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let fields: Vec<_> = line.split('\t').collect();
+        let [id, timestamp, revision, label, source, day, problem, nanos] = match fields[..] {
+            [id, a, b, c, d, e, f, g] => [id, a, b, c, d, e, f, g],
+            [a, b, c, d, e, f, g] => ["", a, b, c, d, e, f, g],
+            _ => bail!("history line {}: expected 8 fields", i + 1),
+        };
+        let bad_field = || anyhow!("history line {}: malformed field", i + 1);
+        let timestamp: u64 = timestamp.parse().map_err(|_| bad_field())?;
+        let day: usize = day.parse().map_err(|_| bad_field())?;
+        let problem: usize = problem.parse().map_err(|_| bad_field())?;
+        let nanos: u64 = nanos.parse().map_err(|_| bad_field())?;
+
+        let same_run = runs
+            .last()
+            .map(|r| r.key() == (id, timestamp, revision, label, source))
+            .unwrap_or(false);
+        if !same_run {
+            runs.push(Run {
+                id: id.to_string(),
+                timestamp,
+                revision: revision.to_string(),
+                label: label.to_string(),
+                source: source.to_string(),
+                times: BTreeMap::new(),
+            });
+        }
+
+        runs.last_mut()
+            .unwrap()
+            .times
+            .insert((day, problem), Duration::from_nanos(nanos));
+    }
+
+    Ok(runs)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Change {
+    pub day: usize,
+    pub problem: usize,
+    pub before: Duration,
+    pub after: Duration,
+}
+
+impl Change {
+    // Relative change in percent. Positive means slower. None if the
+    // baseline took no measurable time, since any change is then infinite.
+    pub fn percent(&self) -> Option<f64> {
+        if self.before.is_zero() {
+            return None;
+        }
+        Some((self.after.as_secs_f64() / self.before.as_secs_f64() - 1.0) * 100.0)
+    }
+}
+
+// Picks the latest run and the run to compare it against: the most recent
+// earlier run from the same source, or the most recent one matching
+// `baseline`.
+pub fn select<'a>(runs: &'a [Run], baseline: Option<&str>) -> Result<(&'a Run, &'a Run)> {
+    let (latest, earlier) = runs
+        .split_last()
+        .ok_or_else(|| anyhow!("no recorded runs"))?;
+
+    let base = earlier
+        .iter()
+        .rev()
+        .filter(|r| r.source == latest.source)
+        .find(|r| baseline.map(|name| r.matches(name)).unwrap_or(true));
+
+    match (base, baseline) {
+        (Some(base), _) => Ok((base, latest)),
+        (None, Some(name)) => bail!("no {} run matches baseline {}", latest.source, name),
+        (None, None) => bail!("no earlier {} run to compare against", latest.source),
+    }
+}
+
+pub fn compare(base: &Run, latest: &Run) -> Vec<Change> {
+    latest
+        .times
+        .iter()
+        .filter_map(|(&(day, problem), &after)| {
+            let &before = base.times.get(&(day, problem))?;
+            Some(Change {
+                day,
+                problem,
+                before,
+                after,
+            })
+        })
+        .collect()
+}
+
+// Describes the checked out revision, e.g. "4fccb1b" or "4fccb1b-dirty".
+fn git_revision() -> String {
+    Command::new("git")
+        .args(["describe", "--always", "--dirty"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .and_then(|o| String::from_utf8(o.stdout).ok())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(timestamp: u64, revision: &str, label: &str, times: &[(usize, usize, u64)]) -> Run {
+        Run {
+            id: format!("id{}", timestamp),
+            timestamp,
+            revision: revision.to_string(),
+            label: label.to_string(),
+            source: "run-all".to_string(),
+            times: times
+                .iter()
+                .map(|&(d, p, ms)| ((d, p), Duration::from_millis(ms)))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn round_trip_test() {
+        let runs = vec![
+            run(100, "abc1234", "-", &[(1, 1, 5), (1, 2, 7)]),
+            run(200, "def5678", "fast", &[(1, 1, 4)]),
+        ];
+        let contents: String = runs.iter().map(|r| r.format()).collect();
+        assert_eq!(parse(&contents).unwrap(), runs);
+
+        // Runs in the same second on the same revision stay apart.
+        let mut same = run(100, "abc1234", "-", &[(1, 1, 5)]);
+        same.id = "other".to_string();
+        let contents = runs[0].format() + &same.format();
+        assert_eq!(parse(&contents).unwrap().len(), 2);
+
+        // Lines from before runs had ids.
+        let old = "100\tabc1234\t-\trun-all\t1\t1\t5000000\n\
+                   100\tabc1234\t-\trun-all\t1\t2\t7000000\n";
+        let parsed = parse(old).unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!((parsed[0].id.as_str(), parsed[0].times.len()), ("", 2));
+    }
+
+    #[test]
+    fn label_test() {
+        assert_eq!(Run::new("bench", None).unwrap().label, "-");
+        assert_eq!(Run::new("bench", Some("fast")).unwrap().label, "fast");
+        assert!(Run::new("bench", Some("-")).is_err());
+        assert!(Run::new("bench", Some("a\tb")).is_err());
+        assert!(Run::new("bench", Some("a\nb")).is_err());
+        assert!(Run::new("bench", Some("")).is_err());
+        assert_ne!(
+            Run::new("bench", None).unwrap().id,
+            Run::new("bench", None).unwrap().id
+        );
+    }
+
+    #[test]
+    fn compare_test() {
+        let runs = vec![
+            run(100, "abc1234", "base", &[(1, 1, 10), (1, 2, 10)]),
+            run(200, "bcd2345", "-", &[(1, 1, 10), (1, 2, 10)]),
+            run(300, "cde3456", "-", &[(1, 1, 15), (1, 2, 9)]),
+        ];
+
+        let (base, latest) = select(&runs, None).unwrap();
+        assert_eq!(base.timestamp, 200);
+        assert_eq!(latest.timestamp, 300);
+
+        let (base, _) = select(&runs, Some("base")).unwrap();
+        assert_eq!(base.timestamp, 100);
+        let (base, _) = select(&runs, Some("bcd")).unwrap();
+        assert_eq!(base.timestamp, 200);
+        assert!(select(&runs, Some("nope")).is_err());
+
+        let changes = compare(base, latest);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].percent().map(f64::round), Some(50.0));
+        assert_eq!(changes[1].percent().map(f64::round), Some(-10.0));
+
+        let zero = Change {
+            day: 1,
+            problem: 1,
+            before: Duration::ZERO,
+            after: Duration::from_millis(1),
+        };
+        assert_eq!(zero.percent(), None);
+    }
+}
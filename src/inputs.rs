@@ -0,0 +1,93 @@
+use std::{
+    borrow::Cow,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+
+use crate::runner;
+
+pub const DEFAULT_DIR: &str = "puzzle-inputs";
+pub const ENV_VAR: &str = "AOC_INPUTS_DIR";
+
+// Reads the input for a day from `dir/dayNN.txt`. If there is no such file,
+// falls back to the copy embedded at build time, which is only there when the
+// `embedded-inputs` feature is enabled.
+pub fn load(dir: &Path, day: usize) -> Result<Cow<'static, str>> {
+    let solution = runner::lookup(day)?;
+    let path = dir.join(format!("{}.txt", solution.day));
+
+    match std::fs::read_to_string(&path) {
+        Ok(x) => Ok(Cow::Owned(x)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => match solution.input {
+            Some(x) => Ok(Cow::Borrowed(x)),
+            None => Err(Missing(path).into()),
+        },
+        Err(e) => Err(e).with_context(|| format!("failed to read {}", path.display())),
+    }
+}
+
+// Reads an input named on the command line: a file path, or "-" for stdin.
+pub fn read_arg(arg: &str) -> Result<String> {
+    if arg == "-" {
+        let mut input = String::new();
+        std::io::stdin()
+            .read_to_string(&mut input)
+            .context("failed to read input from stdin")?;
+        return Ok(input);
+    }
+
+    std::fs::read_to_string(arg).with_context(|| format!("failed to read {}", arg))
+}
+
+// What stdin is when it is a pipe or a redirected file, as opposed to a
+// terminal or /dev/null, which is what scripts and CI usually leave it as.
+#[cfg(unix)]
+pub fn piped_stdin() -> Option<&'static str> {
+    use std::os::unix::fs::FileTypeExt;
+
+    match std::fs::metadata("/dev/stdin") {
+        Ok(x) if x.file_type().is_fifo() => Some("a pipe"),
+        Ok(x) if x.is_file() => Some("a redirected file"),
+        _ => None,
+    }
+}
+
+#[cfg(not(unix))]
+pub fn piped_stdin() -> Option<&'static str> {
+    None
+}
+
+// A day whose input could not be found.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Missing(pub PathBuf);
+
+impl std::fmt::Display for Missing {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "no input at {}", self.0.display())
+    }
+}
+
+impl std::error::Error for Missing {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_test() {
+        let dir = std::env::temp_dir().join(format!("aoc-inputs-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("day01.txt"), "R5\n").unwrap();
+
+        assert_eq!(load(&dir, 1).unwrap(), "R5\n");
+        if cfg!(not(feature = "embedded-inputs")) {
+            let err = load(&dir, 2).unwrap_err();
+            assert_eq!(err.downcast_ref(), Some(&Missing(dir.join("day02.txt"))));
+        }
+        assert!(load(&dir, 99).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
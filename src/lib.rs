@@ -0,0 +1,66 @@
+// Solutions to Advent of Code 2025 and the machinery for running, checking and
+// timing them. The `adventofcode2025` binary is a command line client of this
+// crate. It keeps only what is about the command line or this checkout: picking
+// days and problems from arguments, talking to adventofcode.com, tracking
+// submitted guesses, and scaffolding new days into this source tree.
+//
+// To solve a puzzle, look up its day with `runner::lookup`, or just call
+// `solve`. Nothing here prints or installs a panic hook. Binaries that want a
+// panic's location in its report, and no message on stderr, should call
+// `runner::install_panic_hook` at startup. `util` and `parser::prelude` hold
+// the helpers the days are built from.
+
+pub mod alloc;
+pub mod answer;
+pub mod answers;
+pub mod bench;
+pub mod crosscheck;
+pub mod examples;
+pub mod generate;
+pub mod history;
+pub mod inputs;
+pub mod normalize;
+pub mod params;
+pub mod parser;
+pub mod report;
+pub mod runner;
+pub mod scale;
+pub mod serve;
+pub mod solutions;
+pub mod util;
+
+#[macro_use]
+extern crate lazy_static;
+
+use anyhow::Result;
+
+pub use answer::Answer;
+
+// Solves one part of a day on `input` with the day's default parameters.
+pub fn solve(day: usize, part: usize, input: &str) -> Result<Answer> {
+    let report = runner::run_problems(
+        day,
+        &[part],
+        Ok(input),
+        &[],
+        solutions::DEFAULT_VARIANT,
+        None,
+    )
+    .reports
+    .remove(0);
+    Ok(report.outcome?.answer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solve_test() {
+        let input = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n";
+        assert_eq!(solve(1, 1, input).unwrap(), Answer::from(3u64));
+        assert_eq!(solve(1, 2, input).unwrap(), Answer::from(6u64));
+        assert!(solve(1, 3, input).is_err());
+        assert!(solve(99, 1, input).is_err());
+    }
+}
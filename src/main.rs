@@ -1,24 +1,39 @@
-mod parser;
-mod solutions;
-mod util;
-
-#[macro_use]
-extern crate lazy_static;
+mod aoc;
+mod guesses;
+mod scaffold;
+mod select;
 
 use std::{
-    cmp::Ordering,
-    time::{Duration, Instant},
+    io::Write,
+    net::TcpListener,
+    path::{Path, PathBuf},
+    time::Duration,
 };
 
-use anyhow::{Context, Result, anyhow};
-use clap::{Parser, Subcommand};
-use rayon::prelude::*;
+use anyhow::{Context, Result, bail};
+use clap::{Args, Parser, Subcommand};
+
+use adventofcode2025::{
+    alloc, answer, answers, bench, crosscheck, examples, generate, history, inputs, normalize,
+    params, report, runner, scale, serve, solutions,
+};
+use report::{Failure, Format, Report, Status};
+use runner::run_problems;
+use solutions::DEFAULT_VARIANT;
+
+// Counts allocations for --alloc. Only allocations made while counting is
+// enabled pay for more than a relaxed load.
+#[global_allocator]
+static GLOBAL: alloc::Counting = alloc::Counting;
 
 #[derive(Parser)]
 #[command(name = "aoc2025")]
 #[command(author = "Stephen Weinberg")]
 #[command(about = "Solves Advent of Code 2025", long_about = None)]
 struct Cli {
+    /// Directory holding the puzzle inputs, named dayNN.txt
+    #[arg(long, global = true, env = inputs::ENV_VAR, default_value = inputs::DEFAULT_DIR)]
+    inputs_dir: PathBuf,
     #[command(subcommand)]
     commands: Commands,
 }
@@ -28,98 +43,810 @@ enum Commands {
     Run {
         day: usize,
         problem: usize,
+        #[command(flatten)]
+        source: SourceArgs,
+        /// Which implementation of the problem to run
+        #[arg(long, default_value = solutions::DEFAULT_VARIANT)]
+        variant: String,
+        #[arg(long, value_enum, default_value_t)]
+        format: Format,
+        /// Give up on the problem after this long, e.g. "30s"
+        #[arg(long, value_parser = humantime::parse_duration)]
+        timeout: Option<Duration>,
+        /// Report allocations and peak heap use of each problem
         #[arg(long)]
-        input: Option<String>,
+        alloc: bool,
     },
     RunAll {
         #[arg(long)]
         parallel: bool,
+        #[arg(long, value_enum, default_value_t)]
+        format: Format,
+        /// Give up on each problem after this long, e.g. "30s". A problem
+        /// that times out can't be stopped and keeps running in the
+        /// background, which slows down the days timed after it.
+        #[arg(long, value_parser = humantime::parse_duration)]
+        timeout: Option<Duration>,
+        #[command(flatten)]
+        select: select::Selection,
+        /// Finish with a summary of the N slowest problems
+        #[arg(long, value_name = "N")]
+        slowest: Option<usize>,
+        /// Report allocations and peak heap use of each problem
+        #[arg(long)]
+        alloc: bool,
+        #[command(flatten)]
+        record: RecordArgs,
+    },
+    Verify {
+        #[arg(long, default_value = answers::DEFAULT_DIR)]
+        answers: PathBuf,
+    },
+    Bench {
+        day: Option<usize>,
+        problem: Option<usize>,
+        #[arg(long, default_value_t = 3)]
+        warmup: usize,
+        #[arg(long, default_value_t = 20)]
+        samples: usize,
+        /// Keep sampling until the confidence interval is tight enough or
+        /// this much time has passed, e.g. "10s".
+        #[arg(long, value_parser = humantime::parse_duration)]
+        budget: Option<Duration>,
+        /// Target half-width of the 95% confidence interval, relative to the
+        /// mean. Only used with --budget.
+        #[arg(long, default_value_t = 0.01)]
+        precision: f64,
+        /// Set one of the day's parameters, e.g. "n=10"
+        #[arg(
+            long = "param",
+            value_name = "NAME=VALUE",
+            value_parser = params::parse_assignment,
+            requires = "day"
+        )]
+        params: Vec<(String, i64)>,
+        /// Report allocations and peak heap use of each problem
+        #[arg(long)]
+        alloc: bool,
+        #[command(flatten)]
+        record: RecordArgs,
+    },
+    /// Compares the latest recorded run against an earlier one
+    Compare {
+        /// Label or git revision of the run to compare against. Defaults to
+        /// the previous run.
+        #[arg(long)]
+        baseline: Option<String>,
+        /// Slowdown, in percent, above which a problem is flagged.
+        #[arg(long, default_value_t = 10.0)]
+        threshold: f64,
+        #[arg(long, default_value = history::DEFAULT_PATH)]
+        history: PathBuf,
+    },
+    /// Downloads a day's puzzle input into the inputs directory
+    Fetch {
+        day: usize,
+        #[command(flatten)]
+        config: aoc::Config,
+    },
+    /// Solves a problem and submits the answer, unless earlier guesses rule
+    /// it out
+    Submit {
+        day: usize,
+        part: usize,
+        #[arg(long, default_value = guesses::DEFAULT_PATH)]
+        guesses: PathBuf,
+        #[command(flatten)]
+        config: aoc::Config,
+    },
+    /// Prints a random input for a day, e.g. for stress testing
+    Gen {
+        day: usize,
+        /// Seed for the random generator. A random one is picked and printed
+        /// to stderr when not given.
+        #[arg(long)]
+        seed: Option<u64>,
+        /// How big an input to make. What this counts depends on the day,
+        /// e.g. lines for day01 or the side of the grid for day04.
+        #[arg(long, default_value_t = 100)]
+        size: usize,
     },
+    /// Checks a day's problems against their brute-force oracles, on the
+    /// examples and on generated inputs
+    Crosscheck {
+        day: usize,
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+        /// Seed of the first generated input
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// Number of inputs to generate
+        #[arg(long, default_value_t = 200)]
+        runs: usize,
+        /// Size of the last generated input. Sizes grow from 1 up to this.
+        #[arg(long, default_value_t = 20)]
+        size: usize,
+        /// Set one of the day's parameters, e.g. "n=10"
+        #[arg(long = "param", value_name = "NAME=VALUE", value_parser = params::parse_assignment)]
+        params: Vec<(String, i64)>,
+    },
+    /// Times a day on generated inputs of growing size and estimates how
+    /// fast its running time grows. Growth is measured against the length
+    /// of the input, so a grid's time grows with its area, not its side.
+    Scale {
+        day: usize,
+        /// Size of the first input
+        #[arg(
+            long,
+            default_value_t = 16,
+            value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+        )]
+        start: usize,
+        /// How much bigger each input is than the last, above 1
+        #[arg(long, default_value_t = 2.0, value_parser = scale::parse_factor)]
+        factor: f64,
+        #[arg(long, default_value_t = 8)]
+        steps: usize,
+        /// Stop growing once a step takes longer than this, e.g. "2s". A
+        /// problem that takes longer than this on its own is cut short.
+        #[arg(long, default_value = "2s", value_parser = humantime::parse_duration)]
+        limit: Duration,
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// Timed runs per input. The median is used.
+        #[arg(long, default_value_t = 5)]
+        samples: usize,
+        /// Set one of the day's parameters, e.g. "n=10"
+        #[arg(long = "param", value_name = "NAME=VALUE", value_parser = params::parse_assignment)]
+        params: Vec<(String, i64)>,
+    },
+    /// Serves the solutions over a local HTTP/JSON API. GET /days lists the
+    /// days, and POST /day/N/part/P solves a part with the body as input.
+    Serve {
+        #[arg(long, default_value_t = 2025)]
+        port: u16,
+        /// Give up on a solve after this long, e.g. "30s"
+        #[arg(long, default_value = "10s", value_parser = humantime::parse_duration)]
+        timeout: Duration,
+        /// Turn away connections beyond this many at once
+        #[arg(long, default_value_t = 16)]
+        max_connections: usize,
+        /// Let pages from this origin send requests, or from any with "*"
+        #[arg(long)]
+        allow_origin: Option<String>,
+    },
+    /// Solves one request for serve, which runs each in its own process
+    #[command(hide = true)]
+    ServeWorker,
+    /// Creates a new day from the template and registers it
+    NewDay {
+        day: usize,
+        /// File holding the puzzle's example input, saved to puzzle-examples
+        #[arg(long)]
+        example: Option<PathBuf>,
+        /// Expected answer to problem 1 on the example input
+        #[arg(long, requires = "example")]
+        part1: Option<String>,
+        /// Expected answer to problem 2 on the example input
+        #[arg(long, requires = "example")]
+        part2: Option<String>,
+        /// Root of the crate to add the day to
+        #[arg(long, default_value = env!("CARGO_MANIFEST_DIR"))]
+        root: PathBuf,
+    },
+}
+
+// Where `run` gets its input and parameters from.
+#[derive(Args)]
+struct SourceArgs {
+    /// Input file to use instead of the day's input, or "-" for stdin.
+    /// Piped or redirected stdin is used, with a warning, when this is not given.
+    #[arg(long)]
+    input: Option<String>,
+    /// Use example K from puzzle-examples and check the answer. Without K,
+    /// the first example with an answer for this problem is used.
+    #[arg(long, value_name = "K", conflicts_with = "input")]
+    example: Option<Option<usize>>,
+    /// Set one of the day's parameters, e.g. "n=10"
+    #[arg(long = "param", value_name = "NAME=VALUE", value_parser = params::parse_assignment)]
+    params: Vec<(String, i64)>,
+}
+
+#[derive(Args)]
+struct RecordArgs {
+    /// Append timings to the history file
+    #[arg(long)]
+    record: bool,
+    /// Name the recorded run so it can be used as a baseline
+    #[arg(long, requires = "record", value_parser = history::parse_label)]
+    label: Option<String>,
+    #[arg(long, default_value = history::DEFAULT_PATH)]
+    history: PathBuf,
+}
+
+impl RecordArgs {
+    fn save(&self, source: &str, times: &[(usize, usize, Duration)]) -> Result<()> {
+        if !self.record {
+            return Ok(());
+        }
+
+        let mut run = history::Run::new(source, self.label.as_deref())?;
+        run.times.extend(times.iter().map(|&(d, p, t)| ((d, p), t)));
+        history::append(&self.history, &run)
+    }
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
+    runner::install_panic_hook();
 
     match cli.commands {
         Commands::Run {
             day,
             problem,
-            input,
-        } => run(day, problem, input),
-        Commands::RunAll { parallel } => run_all(parallel),
+            source,
+            variant,
+            format,
+            timeout,
+            alloc,
+        } => {
+            enable_alloc(alloc);
+            run(
+                &cli.inputs_dir,
+                day,
+                problem,
+                source,
+                &variant,
+                format,
+                timeout,
+            )
+        }
+        Commands::RunAll {
+            parallel,
+            format,
+            timeout,
+            select,
+            slowest,
+            alloc,
+            record,
+        } => {
+            enable_alloc(alloc);
+            run_all(
+                &cli.inputs_dir,
+                parallel,
+                format,
+                timeout,
+                &select,
+                slowest,
+                &record,
+            )
+        }
+        Commands::Verify { answers } => verify(&cli.inputs_dir, &answers),
+        Commands::Bench {
+            day,
+            problem,
+            warmup,
+            samples,
+            budget,
+            precision,
+            params,
+            alloc,
+            record,
+        } => {
+            enable_alloc(alloc);
+            let opts = bench::Options {
+                warmup,
+                samples,
+                budget,
+                precision,
+            };
+            run_bench(&cli.inputs_dir, day, problem, &params, &opts, &record)
+        }
+        Commands::Compare {
+            baseline,
+            threshold,
+            history,
+        } => compare(&history, baseline.as_deref(), threshold),
+        Commands::Fetch { day, config } => {
+            aoc::fetch(&config.client()?, &cli.inputs_dir, day).map(|_| ())
+        }
+        Commands::Submit {
+            day,
+            part,
+            guesses,
+            config,
+        } => submit(&cli.inputs_dir, day, part, &guesses, &config),
+        Commands::Gen { day, seed, size } => gen_input(day, seed, size),
+        Commands::Crosscheck {
+            day,
+            part,
+            seed,
+            runs,
+            size,
+            params,
+        } => {
+            let opts = crosscheck::Options { seed, runs, size };
+            run_crosscheck(day, part, &params, &opts)
+        }
+        Commands::Scale {
+            day,
+            start,
+            factor,
+            steps,
+            limit,
+            seed,
+            samples,
+            params,
+        } => {
+            let opts = scale::Options {
+                start,
+                factor,
+                steps,
+                limit,
+                seed,
+                samples,
+            };
+            run_scale(day, &params, &opts)
+        }
+        Commands::Serve {
+            port,
+            timeout,
+            max_connections,
+            allow_origin,
+        } => {
+            // Only local clients can reach this.
+            let listener = TcpListener::bind(("127.0.0.1", port))
+                .with_context(|| format!("failed to listen on port {}", port))?;
+            let exe = std::env::current_exe().context("failed to find this executable")?;
+            let opts = serve::Options {
+                timeout,
+                max_connections,
+                allow_origin,
+                worker: Some(vec![exe.into(), "serve-worker".into()]),
+                log: |message| eprintln!("{}", message),
+            };
+            eprintln!("Listening on http://{}", listener.local_addr()?);
+            serve::serve(listener, opts)
+        }
+        Commands::ServeWorker => serve::work(),
+        Commands::NewDay {
+            day,
+            example,
+            part1,
+            part2,
+            root,
+        } => {
+            let example = scaffold::Example {
+                input: example
+                    .map(|x| std::fs::read_to_string(x).context("failed to read example file"))
+                    .transpose()?,
+                answers: [part1, part2],
+            };
+            scaffold::new_day(&root, &cli.inputs_dir, day, &example)
+        }
     }
 }
 
-fn run(day: usize, problem: usize, input: Option<String>) -> Result<()> {
-    let flag_input = input
-        .as_ref()
-        .map(|x| std::fs::read_to_string(x).context("failed to read input file"))
-        .transpose()?;
+fn enable_alloc(enable: bool) {
+    if enable {
+        alloc::enable();
+    }
+}
+
+fn run(
+    inputs_dir: &Path,
+    day: usize,
+    problem: usize,
+    source: SourceArgs,
+    variant: &str,
+    format: Format,
+    timeout: Option<Duration>,
+) -> Result<()> {
+    let SourceArgs {
+        input,
+        example,
+        mut params,
+    } = source;
+    let mut expected = None;
+    let input = match (input, example) {
+        (_, Some(index)) => {
+            let dir = Path::new(examples::DEFAULT_DIR);
+            let example = examples::select(dir, runner::lookup(day)?.day, problem, index)?;
+            // The answer only holds with the example's parameters, but those
+            // given on the command line still win.
+            if params.is_empty() {
+                expected = example.answer(problem).cloned();
+            }
+            params.splice(..0, example.params);
+            Some(example.input)
+        }
+        (Some(arg), None) => Some(inputs::read_arg(&arg)?),
+        (None, None) => match inputs::piped_stdin() {
+            // Easy to do by accident, e.g. inside a `while read` loop, so say
+            // where the input came from.
+            Some(source) => {
+                eprintln!(
+                    "warning: reading the input from stdin, which is {}. \
+                     Pass --input to choose the input explicitly.",
+                    source
+                );
+                Some(inputs::read_arg("-")?)
+            }
+            None => None,
+        },
+    };
+
+    let mut reports = match input {
+        Some(input) => {
+            let run = run_problems(day, &[problem], Ok(&input), &params, variant, timeout);
+            print_warnings(day, &run.warnings);
+            run.reports
+        }
+        None => run_day(inputs_dir, day, &[problem], &params, variant, timeout),
+    };
+    let report = reports.remove(0);
+
+    if format == Format::Text {
+        let solved = report.outcome?;
+        println!("{}", solved.answer);
+        println!(
+            "\nComputed in {:?} (parse {:?}, solve {:?})",
+            solved.duration(),
+            solved.parse,
+            solved.solve
+        );
+        if let Some(usage) = solved.alloc {
+            println!("Allocated {}", usage);
+        }
+        return check_expected(&solved.answer, expected.as_ref());
+    }
 
-    let (ans, duration) = run_problem(day, problem, flag_input.as_deref())?;
+    let status = report.status();
+    let answer = report.answer().cloned();
+    report::write(std::io::stdout().lock(), &[report], format)?;
+
+    if status != Status::Ok {
+        bail!("day {} problem {} failed", day, problem);
+    }
 
-    println!("{}", ans);
-    println!("\nComputed in {:?}", duration);
+    check_expected(&answer.unwrap(), expected.as_ref())
+}
 
+fn check_expected(answer: &answer::Answer, expected: Option<&answer::Answer>) -> Result<()> {
+    match expected {
+        Some(expected) if answer != expected => bail!("expected {}, got {}", expected, answer),
+        Some(_) => eprintln!("Matches the expected answer"),
+        None => {}
+    }
     Ok(())
 }
 
-fn run_all(parallel: bool) -> Result<()> {
-    let days = {
-        let mut d: Vec<usize> = solutions::SOLUTIONS.keys().copied().collect();
-        d.sort_unstable();
-        d
-    };
+fn run_all(
+    inputs_dir: &Path,
+    parallel: bool,
+    format: Format,
+    timeout: Option<Duration>,
+    select: &select::Selection,
+    slowest: Option<usize>,
+    record: &RecordArgs,
+) -> Result<()> {
+    let days = select.days(solutions::SOLUTIONS.keys().copied())?;
+    let runs = runner::run_days(inputs_dir, &days, &select.parts(), timeout, parallel);
+    let mut reports = Vec::new();
+    for run in runs {
+        print_warnings(run.day, &run.warnings);
+        reports.extend(run.reports);
+    }
 
-    let mut times: Vec<_> = if parallel {
-        days.par_iter()
-            .copied()
-            .flat_map(|day| [(day, 1), (day, 2)])
-            .map(|(day, problem)| (day, problem, run_problem(day, problem, None)))
-            .collect()
-    } else {
-        days.iter()
-            .copied()
-            .flat_map(|day| [(day, 1), (day, 2)])
-            .map(|(day, problem)| (day, problem, run_problem(day, problem, None)))
-            .collect()
-    };
+    if format == Format::Text {
+        report::sort_by_duration(&mut reports);
+    }
+    report::write(std::io::stdout().lock(), &reports, format)?;
 
-    // Sort by duration in descending order. Errors are sorted at the bottom
-    // by day/part.
-    times.sort_by(|a, b| match (&a.2, &b.2) {
-        (Ok(a_res), Ok(b_res)) => a_res.1.cmp(&b_res.1).reverse(),
-        (Err(_), Err(_)) => a.0.cmp(&b.0).then_with(|| a.1.cmp(&b.1)),
-        (Ok(_), Err(_)) => Ordering::Less,
-        (Err(_), Ok(_)) => Ordering::Greater,
-    });
-
-    for (day, problem, res) in &times {
-        if let Ok((_, duration)) = res {
-            println!("{:2}-{}: {:?}", day, problem, duration);
+    if let Some(n) = slowest {
+        // Keep stdout parseable for the other formats.
+        if format == Format::Text {
+            print_slowest(&mut std::io::stdout(), &reports, n)?;
         } else {
-            println!("{:2}-{}: ERROR", day, problem);
+            print_slowest(&mut std::io::stderr(), &reports, n)?;
+        }
+    }
+
+    let successes: Vec<_> = reports
+        .iter()
+        .filter_map(|r| Some((r.day, r.problem, r.duration()?)))
+        .collect();
+    record.save("run-all", &successes)
+}
+
+fn print_slowest(out: &mut impl Write, reports: &[Report], n: usize) -> Result<()> {
+    let slowest = report::slowest(reports, n);
+    writeln!(out, "\nSlowest {}:", slowest.len())?;
+    for (r, duration) in slowest {
+        writeln!(out, "{:2}-{}: {:?}", r.day, r.problem, duration)?;
+    }
+    Ok(())
+}
+
+// Runs the given problems of a day on its input from `inputs_dir`, printing
+// any warnings about the input.
+fn run_day(
+    inputs_dir: &Path,
+    day: usize,
+    problems: &[usize],
+    params: &[(String, i64)],
+    variant: &str,
+    timeout: Option<Duration>,
+) -> Vec<Report> {
+    let run = runner::run_day(inputs_dir, day, problems, params, variant, timeout);
+    print_warnings(day, &run.warnings);
+    run.reports
+}
+
+fn print_warnings(day: usize, warnings: &[normalize::Warning]) {
+    for warning in warnings {
+        eprintln!("warning: day{:02} input: {}", day, warning);
+    }
+}
+
+fn verify(inputs_dir: &Path, answers_dir: &Path) -> Result<()> {
+    let days = runner::days();
+    let answers = answers::Answers::load(
+        answers_dir,
+        days.iter().map(|day| (*day, solutions::SOLUTIONS[day])),
+    )?;
+
+    let mut failures = 0;
+    for run in runner::run_days(inputs_dir, &days, &[1, 2], None, false) {
+        print_warnings(run.day, &run.warnings);
+        for r in &run.reports {
+            let check = answers.check(r);
+            if check.is_failure() {
+                failures += 1;
+            }
+            println!("{:2}-{}: {}", r.day, r.problem, check);
+        }
+    }
+
+    if failures > 0 {
+        bail!("{} problem(s) failed verification", failures);
+    }
+
+    Ok(())
+}
+
+fn run_bench(
+    inputs_dir: &Path,
+    day: Option<usize>,
+    problem: Option<usize>,
+    params: &[(String, i64)],
+    opts: &bench::Options,
+    record: &RecordArgs,
+) -> Result<()> {
+    let days = match day {
+        Some(day) => vec![day],
+        None => runner::days(),
+    };
+    let problems = match problem {
+        Some(problem) => vec![problem],
+        None => vec![1, 2],
+    };
+
+    let mut medians = Vec::new();
+    for day in days {
+        if let Err(e) = bench_day(inputs_dir, day, &problems, params, opts, &mut medians) {
+            let failure = Failure::from(e);
+            println!("{:2}: {} ({})", day, failure.status, failure);
+        }
+    }
+
+    record.save("bench", &medians)
+}
+
+// Benchmarks a day on its input, printing a line per row. Records the median
+// parse plus solve time of each problem's default in `medians`.
+fn bench_day(
+    inputs_dir: &Path,
+    day: usize,
+    problems: &[usize],
+    params: &[(String, i64)],
+    opts: &bench::Options,
+    medians: &mut Vec<(usize, usize, Duration)>,
+) -> Result<()> {
+    let solution = runner::lookup(day)?;
+    let params = params::Params::resolve(solution.params, params)?;
+    let (input, warnings) = runner::prepare(solution, &inputs::load(inputs_dir, day)?);
+    print_warnings(day, &warnings);
+
+    let mut disagree = false;
+    let day_medians = bench::bench_day(solution, &input, problems, &params, opts, |row| {
+        let label = match (row.problem, row.variant) {
+            (None, _) => format!("{:2}-p", day),
+            (Some(problem), None) => format!("{:2}-{}", day, problem),
+            (Some(problem), Some(variant)) => format!("{:2}-{} {}", day, problem, variant),
+        };
+        match row.outcome {
+            bench::Outcome::Timed {
+                stats,
+                alloc,
+                relative,
+            } => {
+                let relative = relative
+                    .map(|x| format!(" ({:.2}x default)", x))
+                    .unwrap_or_default();
+                println!(
+                    "{}: {}{}{}",
+                    label,
+                    stats,
+                    report::alloc_suffix(alloc),
+                    relative
+                );
+            }
+            bench::Outcome::Mismatch { got, expected } => {
+                println!("{}: MISMATCH (got {}, expected {})", label, got, expected);
+                disagree = true;
+            }
+            bench::Outcome::Failed(failure) => println!("{}: ERROR ({})", label, failure),
         }
+    })?;
+    medians.extend(day_medians.into_iter().map(|(p, median)| (day, p, median)));
+
+    if disagree {
+        bail!("variants disagree");
     }
+    Ok(())
+}
+
+fn submit(
+    inputs_dir: &Path,
+    day: usize,
+    part: usize,
+    guesses_path: &Path,
+    config: &aoc::Config,
+) -> Result<()> {
+    let report = run_day(inputs_dir, day, &[part], &[], DEFAULT_VARIANT, None).remove(0);
+    let answer = report.outcome?.answer;
 
+    let mut guesses = guesses::Guesses::load(guesses_path)?;
+    guesses
+        .check(day, part, &answer)
+        .context("not submitting")?;
+
+    println!("submitting {} for day {} part {}", answer, day, part);
+    let verdict = config.client()?.submit(day, part, &answer)?;
+    guesses.append(
+        guesses_path,
+        guesses::Guess::new(day, part, answer, verdict)?,
+    )?;
+
+    if verdict != guesses::Verdict::Correct {
+        bail!("{}", verdict);
+    }
+    println!("{}", verdict);
     Ok(())
 }
 
-fn run_problem(day: usize, problem: usize, input: Option<&str>) -> Result<(String, Duration)> {
-    let solution = solutions::SOLUTIONS
-        .get(&day)
-        .ok_or(anyhow!("unknown day: {}", day))?;
-    let problem_fn = match problem {
-        1 => solution.problem1,
-        2 => solution.problem2,
-        _ => return Err(anyhow!("unknown problem number: {}", problem)),
+fn gen_input(day: usize, seed: Option<u64>, size: usize) -> Result<()> {
+    let solution = runner::lookup(day)?;
+    let input = match seed {
+        Some(seed) => generate::generate(solution, seed, size)?,
+        None => {
+            let seed = rand::random();
+            let input = generate::generate(solution, seed, size)?;
+            eprintln!("seed: {}", seed);
+            input
+        }
     };
+    std::io::stdout().write_all(input.as_bytes())?;
+    Ok(())
+}
+
+fn run_crosscheck(
+    day: usize,
+    part: Option<u8>,
+    params: &[(String, i64)],
+    opts: &crosscheck::Options,
+) -> Result<()> {
+    let solution = runner::lookup(day)?;
+    let dir = Path::new(examples::DEFAULT_DIR);
+    let summaries = crosscheck::crosscheck_day(solution, part.map(usize::from), dir, params, opts)?;
+
+    let mut mismatches = 0;
+    for (problem, summary) in summaries {
+        let Some(mismatch) = summary.mismatch else {
+            println!(
+                "{:2}-{}: {} inputs agree ({} skipped)",
+                day, problem, summary.agreed, summary.skipped
+            );
+            continue;
+        };
 
-    let input = input.unwrap_or(solution.input);
+        mismatches += 1;
+        let got = match &mismatch.got {
+            Ok(answer) => answer.to_string(),
+            Err(f) => format!("{}: {}", f.status, f),
+        };
+        println!(
+            "{:2}-{}: MISMATCH on {} (oracle {}, got {})",
+            day, problem, mismatch.source, mismatch.expected, got
+        );
+        println!("minimized input:\n{}", mismatch.input);
+    }
 
-    let start = Instant::now();
-    let ans = problem_fn(input).context("problemfn failed")?;
-    let end = Instant::now();
+    if mismatches > 0 {
+        bail!("{} problem(s) disagree with their oracle", mismatches);
+    }
+    Ok(())
+}
 
-    Ok((ans, end.duration_since(start)))
+fn run_scale(day: usize, params: &[(String, i64)], opts: &scale::Options) -> Result<()> {
+    let solution = runner::lookup(day)?;
+    let params = params::Params::resolve(solution.params, params)?;
+    let names = ["parse", "part 1", "part 2"];
+
+    println!(
+        "{:>8}  {:>10}  {:>12}  {:>12}  {:>12}",
+        "size", "bytes", names[0], names[1], names[2]
+    );
+    let steps = scale::run(day, &params, opts, |step| {
+        let [parse, part1, part2] = step.times().map(|x| match x {
+            Ok(t) => format!("{:?}", t),
+            Err(f) => f.status.to_string(),
+        });
+        println!(
+            "{:>8}  {:>10}  {:>12}  {:>12}  {:>12}",
+            step.size, step.bytes, parse, part1, part2
+        );
+    })?;
+
+    println!();
+    for (name, k) in names.iter().zip(scale::fit(&steps)) {
+        match k {
+            Some(k) => {
+                let described = scale::describe(k)
+                    .map(|x| format!(" ({})", x))
+                    .unwrap_or_default();
+                println!("{:>6}: time ~ bytes^{:.2}{}", name, k, described);
+            }
+            None => println!("{:>6}: not enough successful steps to fit", name),
+        }
+    }
+
+    Ok(())
+}
+
+fn compare(history_path: &Path, baseline: Option<&str>, threshold: f64) -> Result<()> {
+    let runs = history::load(history_path)?;
+    let (base, latest) = history::select(&runs, baseline)?;
+
+    println!("{}\n  vs {}\n", latest.describe(), base.describe());
+
+    let mut regressions = 0;
+    for change in history::compare(base, latest) {
+        let percent = change.percent();
+        let flag = if percent.is_some_and(|x| x > threshold) {
+            regressions += 1;
+            "  REGRESSION"
+        } else {
+            ""
+        };
+        let percent = match percent {
+            Some(x) => format!("{:+.1}%", x),
+            None => "n/a".to_string(),
+        };
+        println!(
+            "{:2}-{}: {:?} -> {:?} ({}){}",
+            change.day, change.problem, change.before, change.after, percent, flag
+        );
+    }
+
+    if regressions > 0 {
+        bail!(
+            "{} problem(s) slowed down by more than {}%",
+            regressions,
+            threshold
+        );
+    }
+
+    Ok(())
 }
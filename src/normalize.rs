@@ -0,0 +1,136 @@
+// Cleans up inputs pasted from browsers or Windows editors before they are
+// parsed: strips a byte order mark, turns CRLF and lone CR line endings into
+// LF, ends the input with a newline and, unless the day opted out, trims
+// trailing spaces and tabs from each line. Whitespace that can't be fixed
+// safely is only warned about.
+pub fn normalize(input: &str, trim_trailing_space: bool) -> (String, Vec<Warning>) {
+    let mut warnings = Vec::new();
+
+    let input = match input.strip_prefix('\u{feff}') {
+        Some(rest) => {
+            warnings.push(Warning::Bom);
+            rest
+        }
+        None => input,
+    };
+
+    let crs = input.matches('\r').count();
+    if crs > 0 {
+        warnings.push(Warning::CarriageReturns(crs));
+    }
+    let input = input.replace("\r\n", "\n").replace('\r', "\n");
+
+    let mut out = String::with_capacity(input.len() + 1);
+    let mut trimmed = 0;
+    let mut tabs = None;
+    let mut nbsp = None;
+    for (i, line) in input.lines().enumerate() {
+        let line = match line.trim_end_matches([' ', '\t']) {
+            x if trim_trailing_space && x.len() < line.len() => {
+                trimmed += 1;
+                x
+            }
+            _ => line,
+        };
+        if line.contains('\t') {
+            tabs.get_or_insert(i + 1);
+        }
+        if line.contains('\u{a0}') {
+            nbsp.get_or_insert(i + 1);
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+
+    if trimmed > 0 {
+        warnings.push(Warning::TrailingSpace(trimmed));
+    }
+    if let Some(line) = tabs {
+        warnings.push(Warning::Tab(line));
+    }
+    if let Some(line) = nbsp {
+        warnings.push(Warning::NonBreakingSpace(line));
+    }
+    if out.starts_with('\n') {
+        warnings.push(Warning::LeadingBlankLine);
+    }
+
+    (out, warnings)
+}
+
+// Something odd about an input's whitespace.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Warning {
+    Bom,
+    // How many carriage returns were turned into newlines.
+    CarriageReturns(usize),
+    // How many lines had trailing whitespace trimmed.
+    TrailingSpace(usize),
+    // The first line with a tab, which is kept.
+    Tab(usize),
+    // The first line with a non-breaking space, which is kept.
+    NonBreakingSpace(usize),
+    LeadingBlankLine,
+}
+
+impl std::fmt::Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Warning::Bom => write!(f, "stripped a byte order mark"),
+            Warning::CarriageReturns(n) => {
+                write!(f, "converted {} CR line ending{} to LF", n, plural(*n))
+            }
+            Warning::TrailingSpace(n) => {
+                write!(
+                    f,
+                    "trimmed trailing whitespace from {} line{}",
+                    n,
+                    plural(*n)
+                )
+            }
+            Warning::Tab(line) => write!(f, "line {} contains a tab", line),
+            Warning::NonBreakingSpace(line) => {
+                write!(f, "line {} contains a non-breaking space", line)
+            }
+            Warning::LeadingBlankLine => write!(f, "input starts with a blank line"),
+        }
+    }
+}
+
+fn plural(n: usize) -> &'static str {
+    if n == 1 { "" } else { "s" }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_test() {
+        assert_eq!(normalize("a\nb\n", true), ("a\nb\n".to_string(), vec![]));
+        assert_eq!(normalize("a\nb", true), ("a\nb\n".to_string(), vec![]));
+        assert_eq!(normalize("", true), (String::new(), vec![]));
+
+        let (out, warnings) = normalize("\u{feff}a  \r\nb\t\r\n\r\nc\rd", true);
+        assert_eq!(out, "a\nb\n\nc\nd\n");
+        assert_eq!(
+            warnings,
+            [
+                Warning::Bom,
+                Warning::CarriageReturns(4),
+                Warning::TrailingSpace(2)
+            ]
+        );
+
+        let (out, warnings) = normalize("\n1 \n 2\t3 \r\n", false);
+        assert_eq!(out, "\n1 \n 2\t3 \n");
+        assert_eq!(
+            warnings,
+            [
+                Warning::CarriageReturns(1),
+                Warning::Tab(3),
+                Warning::LeadingBlankLine
+            ]
+        );
+    }
+}
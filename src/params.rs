@@ -0,0 +1,107 @@
+use anyhow::{Context, Result, bail};
+use serde::Serialize;
+
+// A named puzzle parameter, for numbers that differ between the example and
+// the real puzzle, like how many pairs day08 connects.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Param {
+    pub name: &'static str,
+    pub default: i64,
+    pub help: &'static str,
+}
+
+// The value of each of a day's parameters.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Params(Vec<(&'static str, i64)>);
+
+impl Params {
+    pub fn defaults(declared: &'static [Param]) -> Self {
+        Params(declared.iter().map(|p| (p.name, p.default)).collect())
+    }
+
+    // The defaults with `overrides` applied in order, so later ones win.
+    pub fn resolve(declared: &'static [Param], overrides: &[(String, i64)]) -> Result<Self> {
+        let mut params = Self::defaults(declared);
+        for (name, value) in overrides {
+            match params.0.iter_mut().find(|(n, _)| n == name) {
+                Some(slot) => slot.1 = *value,
+                None if declared.is_empty() => bail!("this day takes no parameters"),
+                None => bail!(
+                    "unknown parameter {:?}, expected one of:\n{}",
+                    name,
+                    describe(declared)
+                ),
+            }
+        }
+        Ok(params)
+    }
+
+    // Panics if the day didn't declare `name`, since that's a bug in the day.
+    pub fn get(&self, name: &str) -> i64 {
+        self.0
+            .iter()
+            .find(|(n, _)| *n == name)
+            .unwrap_or_else(|| panic!("undeclared parameter {:?}", name))
+            .1
+    }
+}
+
+pub fn describe(declared: &[Param]) -> String {
+    declared
+        .iter()
+        .map(|p| format!("  {}: {} (default {})", p.name, p.help, p.default))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Parses a `name=value` assignment, as given to `--param`.
+pub fn parse_assignment(s: &str) -> Result<(String, i64)> {
+    let (name, value) = s
+        .split_once('=')
+        .with_context(|| format!("expected name=value, got {:?}", s))?;
+    let value = value
+        .trim()
+        .parse()
+        .with_context(|| format!("bad value for {}: {:?}", name.trim(), value.trim()))?;
+    Ok((name.trim().to_string(), value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DECLARED: &[Param] = &[
+        Param {
+            name: "size",
+            default: 100,
+            help: "dial size",
+        },
+        Param {
+            name: "start",
+            default: 50,
+            help: "starting position",
+        },
+    ];
+
+    #[test]
+    fn resolve_test() {
+        let params = Params::resolve(DECLARED, &[]).unwrap();
+        assert_eq!((params.get("size"), params.get("start")), (100, 50));
+
+        let overrides = [("start".to_string(), 1), ("start".to_string(), 2)];
+        let params = Params::resolve(DECLARED, &overrides).unwrap();
+        assert_eq!((params.get("size"), params.get("start")), (100, 2));
+
+        let err = Params::resolve(DECLARED, &[("n".to_string(), 1)]).unwrap_err();
+        assert!(err.to_string().contains("size: dial size (default 100)"));
+        assert!(Params::resolve(&[], &[("n".to_string(), 1)]).is_err());
+    }
+
+    #[test]
+    fn parse_assignment_test() {
+        assert_eq!(parse_assignment("n=10").unwrap(), ("n".to_string(), 10));
+        assert_eq!(parse_assignment(" n = -3 ").unwrap(), ("n".to_string(), -3));
+        assert!(parse_assignment("n").is_err());
+        assert!(parse_assignment("n=x").is_err());
+    }
+}
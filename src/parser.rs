@@ -1,38 +1,38 @@
-use std::str::FromStr;
+use std::{cell::RefCell, str::FromStr};
 
-use nom::combinator::all_consuming;
+use nom::{
+    combinator::all_consuming,
+    error::{ContextError, ErrorKind, FromExternalError},
+};
 use prelude::*;
 
 pub mod prelude {
     pub use nom::{
-        AsChar, IResult, Input, Mode, Parser,
+        AsChar, Input, Mode, Parser,
         branch::alt,
-        bytes::tag,
         character::complete::{
             char, digit1, line_ending, multispace0, multispace1, one_of, space0, space1,
         },
         combinator::{eof, opt, recognize, value},
+        error::context,
         multi::{fold_many1, many1, separated_list1},
         sequence::{delimited, separated_pair, terminated},
     };
 
-    #[allow(unused_imports)]
-    pub use super::{complete, int, uint, ws_all_consuming, ws_line};
+    pub use super::{IResult, complete, int, uint, ws_all_consuming, ws_line};
 }
 
-pub fn uint<'a, T: FromStr>() -> impl Parser<&'a str, Output = T, Error = nom::error::Error<&'a str>>
-{
+pub type IResult<I, O, E = Error<I>> = nom::IResult<I, O, E>;
+
+pub fn uint<'a, T: FromStr>() -> impl Parser<&'a str, Output = T, Error = Error<&'a str>> {
     digit1.map_res(|x: &str| x.parse())
 }
 
-#[allow(dead_code)]
-pub fn int<'a, T: FromStr>() -> impl Parser<&'a str, Output = T, Error = nom::error::Error<&'a str>>
-{
-    let num = (opt(tag("-")), digit1);
+pub fn int<'a, T: FromStr>() -> impl Parser<&'a str, Output = T, Error = Error<&'a str>> {
+    let num = (opt(char('-')), digit1);
     recognize(num).map_res(|x: &str| x.parse())
 }
 
-#[allow(dead_code)]
 pub fn complete<I, P>(parser: P) -> impl Parser<I, Output = P::Output, Error = P::Error>
 where
     I: nom::Input,
@@ -60,3 +60,262 @@ where
     let end_of_line = alt((line_ending, eof));
     delimited(space0, parser, (space0, end_of_line))
 }
+
+// Runs a day's parser over the whole input. When it fails, the error points
+// at the furthest position any parser got to, since that is usually where
+// the input is wrong. A failure there is often swallowed by combinators like
+// `many1`, leaving only an unhelpful error from `all_consuming`.
+pub fn run<'a, O>(
+    input: &'a str,
+    parser: impl FnOnce(&'a str) -> IResult<&'a str, O>,
+) -> Result<O, ParseError> {
+    FURTHEST.with(|f| *f.borrow_mut() = Some(None));
+    let res = parser(input);
+    let furthest = FURTHEST.with(|f| f.borrow_mut().take()).flatten();
+
+    let err = match res {
+        Ok((_, output)) => return Ok(output),
+        Err(nom::Err::Incomplete(_)) => Error {
+            input: &input[input.len()..],
+            expected: Expected::Kind(ErrorKind::Eof),
+        },
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => e,
+    };
+
+    let furthest = furthest.unwrap_or(Furthest {
+        remaining: err.input.len(),
+        expected: vec![err.expected],
+    });
+    Err(ParseError::new(
+        input,
+        input.len() - furthest.remaining,
+        furthest.expected,
+    ))
+}
+
+// The error type of all the parsers. Errors also get recorded in `FURTHEST`,
+// which is what ends up being reported.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Error<I> {
+    pub input: I,
+    pub expected: Expected,
+}
+
+// What a parser was looking for when it failed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Expected {
+    Char(char),
+    Kind(ErrorKind),
+    // The name given to a parser with `context`.
+    Name(&'static str),
+}
+
+impl std::fmt::Display for Expected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expected::Char(c) => write!(f, "`{}`", c.escape_default()),
+            Expected::Kind(kind) => {
+                let name = match kind {
+                    ErrorKind::Digit => "number",
+                    ErrorKind::Alpha => "letter",
+                    ErrorKind::AlphaNumeric => "letter or digit",
+                    ErrorKind::Space => "space",
+                    ErrorKind::MultiSpace => "whitespace",
+                    ErrorKind::CrLf => "line ending",
+                    ErrorKind::Eof => "end of input",
+                    ErrorKind::MapRes => "value in range",
+                    kind => return write!(f, "{}", kind.description().to_lowercase()),
+                };
+                write!(f, "{}", name)
+            }
+            Expected::Name(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+impl<I: Input> Error<I> {
+    fn new(input: I, expected: Expected) -> Self {
+        record(input.input_len(), expected);
+        Self { input, expected }
+    }
+}
+
+impl<I: Input> nom::error::ParseError<I> for Error<I> {
+    fn from_error_kind(input: I, kind: ErrorKind) -> Self {
+        Self::new(input, Expected::Kind(kind))
+    }
+
+    fn append(_input: I, _kind: ErrorKind, other: Self) -> Self {
+        other
+    }
+
+    fn from_char(input: I, c: char) -> Self {
+        Self::new(input, Expected::Char(c))
+    }
+}
+
+impl<I: Input> ContextError<I> for Error<I> {
+    fn add_context(input: I, ctx: &'static str, other: Self) -> Self {
+        // Only name the parser if it failed without getting anywhere.
+        // Otherwise what it was looking for further on is more useful.
+        let remaining = input.input_len();
+        FURTHEST.with(|f| {
+            if let Some(Some(furthest)) = f.borrow_mut().as_mut()
+                && furthest.remaining == remaining
+            {
+                furthest.expected = vec![Expected::Name(ctx)];
+            }
+        });
+        other
+    }
+}
+
+impl<I: Input, E> FromExternalError<I, E> for Error<I> {
+    fn from_external_error(input: I, kind: ErrorKind, _e: E) -> Self {
+        Self::new(input, Expected::Kind(kind))
+    }
+}
+
+// Where the parser that got furthest failed, as the length of the input left
+// there, and everything that was expected at that point.
+struct Furthest {
+    remaining: usize,
+    expected: Vec<Expected>,
+}
+
+thread_local! {
+    // Some while `run` is parsing.
+    static FURTHEST: RefCell<Option<Option<Furthest>>> = const { RefCell::new(None) };
+}
+
+fn record(remaining: usize, expected: Expected) {
+    FURTHEST.with(|f| {
+        let mut f = f.borrow_mut();
+        let Some(furthest) = f.as_mut() else {
+            return;
+        };
+        match furthest {
+            Some(x) if x.remaining == remaining => {
+                if !x.expected.contains(&expected) {
+                    x.expected.push(expected);
+                }
+            }
+            Some(x) if x.remaining < remaining => {}
+            _ => {
+                *furthest = Some(Furthest {
+                    remaining,
+                    expected: vec![expected],
+                })
+            }
+        }
+    });
+}
+
+// A parse failure, located by 1-based line and column.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    // The line the failure is on, without its line ending.
+    pub text: String,
+    pub expected: Vec<Expected>,
+}
+
+impl ParseError {
+    fn new(input: &str, offset: usize, mut expected: Vec<Expected>) -> Self {
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let line_end = input[offset..]
+            .find('\n')
+            .map(|i| offset + i)
+            .unwrap_or(input.len());
+
+        // A list of things can always end, so that is only worth mentioning
+        // when nothing else would do.
+        if expected.len() > 1 {
+            expected.retain(|&x| x != Expected::Kind(ErrorKind::Eof));
+        }
+
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: input[line_start..offset].chars().count() + 1,
+            text: input[line_start..line_end]
+                .trim_end_matches('\r')
+                .to_string(),
+            expected,
+        }
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let expected: Vec<_> = self.expected.iter().map(|x| x.to_string()).collect();
+        let expected = match expected.split_last() {
+            Some((last, [])) => last.clone(),
+            Some((last, rest)) => format!("{} or {}", rest.join(", "), last),
+            None => "something else".to_string(),
+        };
+        write!(
+            f,
+            "line {}, column {}: expected {}",
+            self.line, self.column, expected
+        )?;
+
+        // Keep tabs in the padding so that the caret lines up.
+        let number = self.line.to_string();
+        let pad = " ".repeat(number.len());
+        let caret: String = self
+            .text
+            .chars()
+            .take(self.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        write!(
+            f,
+            "\n{} |\n{} | {}\n{} | {}^",
+            pad, number, self.text, pad, caret
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rotations(input: &str) -> IResult<&str, Vec<(char, u32)>> {
+        let rotation = (alt((char('L'), char('R'))), uint());
+        ws_all_consuming(many1(ws_line(rotation))).parse_complete(input)
+    }
+
+    #[test]
+    fn run_test() {
+        assert_eq!(run("L1\nR2\n", rotations).unwrap(), [('L', 1), ('R', 2)]);
+
+        let err = run("L1\nR2\nX3\nL4\n", rotations).unwrap_err();
+        assert_eq!((err.line, err.column), (3, 1));
+        assert_eq!(
+            err.to_string(),
+            "line 3, column 1: expected `L` or `R`\n  |\n3 | X3\n  | ^"
+        );
+
+        let err = run("L1\r\nR2x\r\n", rotations).unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 3, "R2x"));
+        assert_eq!(err.expected, [Expected::Kind(ErrorKind::CrLf)]);
+
+        let err = run("L\t1\n", rotations).unwrap_err();
+        assert!(err.to_string().ends_with("1 | L\t1\n  |  ^"), "{}", err);
+
+        let named = |input| context("a rotation", rotations).parse_complete(input);
+        let err = run("X", named).unwrap_err();
+        assert_eq!(
+            err.to_string().lines().next(),
+            Some("line 1, column 1: expected a rotation")
+        );
+
+        // Errors outside of `run` aren't recorded.
+        assert!(rotations("X").is_err());
+        assert!(FURTHEST.with(|f| f.borrow().is_none()));
+    }
+}
@@ -0,0 +1,347 @@
+use std::{cmp::Ordering, io::Write, time::Duration};
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::{
+    alloc::Usage,
+    answer::Answer,
+    inputs::Missing,
+    runner::{Panic, Timeout},
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+    Csv,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Status {
+    Ok,
+    Error,
+    Panic,
+    Timeout,
+    Missing,
+}
+
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Status::Ok => "OK",
+            Status::Error => "ERROR",
+            Status::Panic => "PANIC",
+            Status::Timeout => "TIMEOUT",
+            Status::Missing => "MISSING",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+// A successful run of a single problem. The parse time is shared with the
+// other problem of the day when both are run together.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solved {
+    pub answer: Answer,
+    pub parse: Duration,
+    pub solve: Duration,
+    // Allocations made by the parse and solve, when counting was enabled.
+    pub alloc: Option<Usage>,
+}
+
+impl Solved {
+    pub fn duration(&self) -> Duration {
+        self.parse + self.solve
+    }
+}
+
+// A failed run of a single problem. Unlike `anyhow::Error` this can be cloned,
+// which is needed when one failed parse fails several problems.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Failure {
+    pub status: Status,
+    // The error followed by each of its causes, outermost first.
+    pub errors: Vec<String>,
+}
+
+impl From<anyhow::Error> for Failure {
+    fn from(e: anyhow::Error) -> Self {
+        let status = if e.downcast_ref::<Panic>().is_some() {
+            Status::Panic
+        } else if e.downcast_ref::<Timeout>().is_some() {
+            Status::Timeout
+        } else if e.downcast_ref::<Missing>().is_some() {
+            Status::Missing
+        } else {
+            Status::Error
+        };
+
+        Self {
+            status,
+            errors: e.chain().map(|x| x.to_string()).collect(),
+        }
+    }
+}
+
+impl std::fmt::Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.errors.join(": "))
+    }
+}
+
+impl std::error::Error for Failure {}
+
+// The result of running a single problem.
+#[derive(Clone, Debug)]
+pub struct Report {
+    pub day: usize,
+    pub problem: usize,
+    pub outcome: Result<Solved, Failure>,
+}
+
+impl Report {
+    pub fn new(day: usize, problem: usize, outcome: Result<Solved, Failure>) -> Self {
+        Self {
+            day,
+            problem,
+            outcome,
+        }
+    }
+
+    pub fn status(&self) -> Status {
+        match &self.outcome {
+            Ok(_) => Status::Ok,
+            Err(f) => f.status,
+        }
+    }
+
+    pub fn answer(&self) -> Option<&Answer> {
+        self.outcome.as_ref().ok().map(|x| &x.answer)
+    }
+
+    pub fn duration(&self) -> Option<Duration> {
+        self.outcome.as_ref().ok().map(|x| x.duration())
+    }
+
+    fn record(&self) -> Record<'_> {
+        let solved = self.outcome.as_ref().ok();
+        let alloc = solved.and_then(|x| x.alloc);
+        let nanos = |d: Duration| d.as_nanos() as u64;
+        Record {
+            day: self.day,
+            part: self.problem,
+            status: self.status(),
+            answer: self.answer(),
+            duration_ns: self.duration().map(nanos),
+            parse_ns: solved.map(|x| nanos(x.parse)),
+            solve_ns: solved.map(|x| nanos(x.solve)),
+            allocations: alloc.map(|x| x.allocations),
+            alloc_bytes: alloc.map(|x| x.bytes),
+            peak_bytes: alloc.map(|x| x.peak),
+            errors: self
+                .outcome
+                .as_ref()
+                .err()
+                .map(|f| f.errors.as_slice())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+// Serializes as the record `write_json` prints.
+impl Serialize for Report {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        self.record().serialize(s)
+    }
+}
+
+#[derive(Serialize)]
+struct Record<'a> {
+    day: usize,
+    part: usize,
+    status: Status,
+    answer: Option<&'a Answer>,
+    duration_ns: Option<u64>,
+    parse_ns: Option<u64>,
+    solve_ns: Option<u64>,
+    allocations: Option<u64>,
+    alloc_bytes: Option<u64>,
+    peak_bytes: Option<u64>,
+    errors: &'a [String],
+}
+
+pub fn write(w: impl Write, reports: &[Report], format: Format) -> Result<()> {
+    match format {
+        Format::Text => write_text(w, reports),
+        Format::Json => write_json(w, reports),
+        Format::Csv => write_csv(w, reports),
+    }
+}
+
+pub fn write_text(mut w: impl Write, reports: &[Report]) -> Result<()> {
+    for r in reports {
+        match &r.outcome {
+            Ok(s) => writeln!(
+                w,
+                "{:2}-{}: {:?} (parse {:?}, solve {:?}){}",
+                r.day,
+                r.problem,
+                s.duration(),
+                s.parse,
+                s.solve,
+                alloc_suffix(s.alloc)
+            )?,
+            Err(f) => writeln!(w, "{:2}-{}: {}: {}", r.day, r.problem, f.status, f)?,
+        }
+    }
+    Ok(())
+}
+
+// Formats allocations to follow a time, or nothing if they weren't counted.
+pub fn alloc_suffix(usage: Option<Usage>) -> String {
+    usage.map(|x| format!(" [{}]", x)).unwrap_or_default()
+}
+
+pub fn write_json(mut w: impl Write, reports: &[Report]) -> Result<()> {
+    let records: Vec<_> = reports.iter().map(|r| r.record()).collect();
+    serde_json::to_writer_pretty(&mut w, &records)?;
+    writeln!(w)?;
+    Ok(())
+}
+
+pub fn write_csv(mut w: impl Write, reports: &[Report]) -> Result<()> {
+    writeln!(
+        w,
+        "day,part,status,answer,duration_ns,parse_ns,solve_ns,allocations,alloc_bytes,peak_bytes,error"
+    )?;
+    for r in reports {
+        let record = r.record();
+        let number = |x: Option<u64>| x.map(|x| x.to_string()).unwrap_or_default();
+        writeln!(
+            w,
+            "{},{},{},{},{},{},{},{},{},{},{}",
+            record.day,
+            record.part,
+            record.status,
+            csv_field(&record.answer.map(|x| x.to_string()).unwrap_or_default()),
+            number(record.duration_ns),
+            number(record.parse_ns),
+            number(record.solve_ns),
+            number(record.allocations),
+            number(record.alloc_bytes),
+            number(record.peak_bytes),
+            csv_field(&record.errors.join(": ")),
+        )?;
+    }
+    Ok(())
+}
+
+// Sorts the slowest reports first. Failures go last, by day and part.
+pub fn sort_by_duration(reports: &mut [Report]) {
+    reports.sort_by(|a, b| match (a.duration(), b.duration()) {
+        (Some(a_dur), Some(b_dur)) => a_dur.cmp(&b_dur).reverse(),
+        (None, None) => a.day.cmp(&b.day).then_with(|| a.problem.cmp(&b.problem)),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+    });
+}
+
+// The `n` slowest successful reports, slowest first.
+pub fn slowest(reports: &[Report], n: usize) -> Vec<(&Report, Duration)> {
+    let mut timed: Vec<_> = reports
+        .iter()
+        .filter_map(|r| Some((r, r.duration()?)))
+        .collect();
+    timed.sort_by_key(|&(_, duration)| std::cmp::Reverse(duration));
+    timed.truncate(n);
+    timed
+}
+
+// Quotes a field if it contains characters that are special in CSV.
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use anyhow::anyhow;
+
+    fn reports() -> Vec<Report> {
+        vec![
+            Report::new(
+                1,
+                1,
+                Ok(Solved {
+                    answer: 42u64.into(),
+                    parse: Duration::from_nanos(500),
+                    solve: Duration::from_nanos(1000),
+                    alloc: Some(Usage {
+                        allocations: 3,
+                        bytes: 2048,
+                        peak: 1024,
+                        retained: 0,
+                    }),
+                }),
+            ),
+            Report::new(
+                1,
+                2,
+                Err(anyhow!("bad \"input\", line 3")
+                    .context("problemfn failed")
+                    .into()),
+            ),
+        ]
+    }
+
+    #[test]
+    fn csv_test() {
+        let mut out = Vec::new();
+        write_csv(&mut out, &reports()).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "day,part,status,answer,duration_ns,parse_ns,solve_ns,allocations,alloc_bytes,peak_bytes,error\n\
+             1,1,OK,42,1500,500,1000,3,2048,1024,\n\
+             1,2,ERROR,,,,,,,,\"problemfn failed: bad \"\"input\"\", line 3\"\n"
+        );
+    }
+
+    #[test]
+    fn text_test() {
+        let mut reports = reports();
+        reports.reverse();
+        sort_by_duration(&mut reports);
+        let mut out = Vec::new();
+        write_text(&mut out, &reports).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            " 1-1: 1.5µs (parse 500ns, solve 1µs) [3 allocs, 2.0 KiB allocated, 1.0 KiB peak]\n \
+             1-2: ERROR: problemfn failed: bad \"input\", line 3\n"
+        );
+
+        let slowest = slowest(&reports, 5);
+        assert_eq!(slowest.len(), 1);
+        assert_eq!(slowest[0].1, Duration::from_nanos(1500));
+    }
+
+    #[test]
+    fn json_test() {
+        let mut out = Vec::new();
+        write_json(&mut out, &reports()).unwrap();
+        let v: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(v[0]["answer"], 42);
+        assert_eq!(v[0]["duration_ns"], 1500);
+        assert_eq!(v[0]["parse_ns"], 500);
+        assert_eq!(v[0]["peak_bytes"], 1024);
+        assert_eq!(v[1]["status"], "ERROR");
+        assert_eq!(v[1]["errors"][1], "bad \"input\", line 3");
+    }
+}
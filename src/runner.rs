@@ -0,0 +1,489 @@
+use std::{
+    any::Any,
+    cell::RefCell,
+    panic::{self, AssertUnwindSafe},
+    path::Path,
+    sync::{Arc, Once, mpsc},
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result, anyhow};
+use rayon::prelude::*;
+
+use crate::{
+    alloc::{Tracker, Usage},
+    answer::Answer,
+    inputs,
+    normalize::{self, Warning},
+    params::Params,
+    report::{Failure, Report, Solved},
+    solutions::{self, DEFAULT_VARIANT, Parsed, Solution},
+};
+
+pub fn lookup(day: usize) -> Result<&'static Solution> {
+    solutions::SOLUTIONS
+        .get(&day)
+        .copied()
+        .ok_or(anyhow!("unknown day: {}", day))
+}
+
+// Every day with a solution, in order.
+pub fn days() -> Vec<usize> {
+    let mut days: Vec<usize> = solutions::SOLUTIONS.keys().copied().collect();
+    days.sort_unstable();
+    days
+}
+
+pub fn parse<'a>(solution: &Solution, input: &'a str) -> Result<(Box<dyn Parsed + 'a>, Cost)> {
+    call(|| (solution.parse)(input)).context("parse failed")
+}
+
+pub fn solve(parsed: &dyn Parsed, problem: usize, params: &Params) -> Result<(Answer, Cost)> {
+    call(|| parsed.solve(problem, params)).context("problemfn failed")
+}
+
+pub fn solve_variant(
+    parsed: &dyn Parsed,
+    problem: usize,
+    variant: &str,
+    params: &Params,
+) -> Result<(Answer, Cost)> {
+    call(|| parsed.variant(problem, variant, params)).context("problemfn failed")
+}
+
+pub fn oracle(parsed: &dyn Parsed, problem: usize, params: &Params) -> Result<(Answer, Cost)> {
+    call(|| parsed.oracle(problem, params)).context("oracle failed")
+}
+
+// What one call into a solution took. `alloc` is only measured once
+// allocation counting has been enabled.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Cost {
+    pub duration: Duration,
+    pub alloc: Option<Usage>,
+}
+
+// The reports of a `run_problems` call, one per problem, and what had to be
+// cleaned up in the input first.
+#[derive(Clone, Debug, Default)]
+pub struct DayRun {
+    pub day: usize,
+    pub warnings: Vec<Warning>,
+    pub reports: Vec<Report>,
+}
+
+// Runs the given problems of a day, parsing the input only once. If the input
+// could not be loaded, every problem fails with that error. `overrides` are
+// applied on top of the day's default parameters, and `variant` picks which
+// implementation of the problems to run. When `timeout` is
+// set each problem gets that long to finish, with the first problem's budget
+// also covering the parse. A problem that runs out of time is abandoned on a
+// detached thread, where it keeps running and competes for the CPU with
+// whatever is timed next, so this is only suitable for short-lived processes.
+pub fn run_problems(
+    day: usize,
+    problems: &[usize],
+    input: Result<&str, Failure>,
+    overrides: &[(String, i64)],
+    variant: &str,
+    timeout: Option<Duration>,
+) -> DayRun {
+    let fail = |failure: Failure| {
+        let mut collector = Collector::new(day, problems);
+        collector.fail_pending(failure);
+        DayRun {
+            day,
+            warnings: Vec::new(),
+            reports: collector.reports,
+        }
+    };
+
+    let solution = match lookup(day) {
+        Ok(x) => x,
+        Err(e) => return fail(e.into()),
+    };
+
+    let input = match input {
+        Ok(x) => x,
+        Err(failure) => return fail(failure),
+    };
+
+    let params = match Params::resolve(solution.params, overrides) {
+        Ok(x) => x,
+        Err(e) => return fail(e.into()),
+    };
+
+    let (input, warnings) = prepare(solution, input);
+    DayRun {
+        day,
+        warnings,
+        reports: solve_problems(
+            day,
+            solution,
+            problems,
+            input.into(),
+            &params,
+            variant,
+            timeout,
+        ),
+    }
+}
+
+// The stack of the thread that solves problems with a timeout. Spawned threads
+// get 2 MiB by default, a quarter of what the main thread has, which deeply
+// recursive solutions outgrow.
+const STACK_SIZE: usize = 64 << 20;
+
+// Runs the given problems of a day on its input from `inputs_dir`.
+pub fn run_day(
+    inputs_dir: &Path,
+    day: usize,
+    problems: &[usize],
+    overrides: &[(String, i64)],
+    variant: &str,
+    timeout: Option<Duration>,
+) -> DayRun {
+    match inputs::load(inputs_dir, day) {
+        Ok(input) => run_problems(day, problems, Ok(&input), overrides, variant, timeout),
+        Err(e) => run_problems(day, problems, Err(e.into()), overrides, variant, timeout),
+    }
+}
+
+// Runs the given problems of each day with its default parameters and
+// variant, on the days' inputs from `inputs_dir`. The days run one at a time
+// unless `parallel` is set, but come back in the order given either way.
+pub fn run_days(
+    inputs_dir: &Path,
+    days: &[usize],
+    problems: &[usize],
+    timeout: Option<Duration>,
+    parallel: bool,
+) -> Vec<DayRun> {
+    let run = |&day: &usize| run_day(inputs_dir, day, problems, &[], DEFAULT_VARIANT, timeout);
+    if parallel {
+        days.par_iter().map(run).collect()
+    } else {
+        days.iter().map(run).collect()
+    }
+}
+
+// Does the work of `run_problems` on a normalized input, with the parameters
+// already resolved.
+pub fn solve_problems(
+    day: usize,
+    solution: &'static Solution,
+    problems: &[usize],
+    input: Arc<str>,
+    params: &Params,
+    variant: &str,
+    timeout: Option<Duration>,
+) -> Vec<Report> {
+    let mut collector = Collector::new(day, problems);
+
+    let Some(timeout) = timeout else {
+        solve_day(solution, &input, params, problems, variant, |event| {
+            collector.handle(event)
+        });
+        return collector.reports;
+    };
+
+    let (tx, rx) = mpsc::channel();
+    let spawned = {
+        let input = input.clone();
+        let params = params.clone();
+        let problems = problems.to_vec();
+        let variant = variant.to_string();
+        std::thread::Builder::new()
+            .name(format!("day{:02}", day))
+            .stack_size(STACK_SIZE)
+            .spawn(move || {
+                solve_day(solution, &input, &params, &problems, &variant, |event| {
+                    // The receiver is gone if we timed out, which is fine.
+                    let _ = tx.send(event);
+                })
+            })
+    };
+    if let Err(e) = spawned {
+        collector.fail_pending(anyhow!(e).context("failed to spawn thread").into());
+        return collector.reports;
+    }
+
+    let mut deadline = Instant::now() + timeout;
+    while let Some(&current) = collector.pending.first() {
+        match rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            Ok(event) => {
+                if let Event::Solved(..) = event {
+                    deadline = Instant::now() + timeout;
+                }
+                collector.handle(event);
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                collector.finish(current, Err(anyhow!(Timeout(timeout)).into()));
+
+                // The worker is stuck, so give the remaining problems a fresh
+                // one.
+                let rest = std::mem::take(&mut collector.pending);
+                let reports = solve_problems(
+                    day,
+                    solution,
+                    &rest,
+                    input.clone(),
+                    params,
+                    variant,
+                    Some(timeout),
+                );
+                collector.reports.extend(reports);
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                collector.fail_pending(anyhow!("problem thread died").into());
+            }
+        }
+    }
+
+    collector.reports
+}
+
+enum Event {
+    Parsed(Result<Cost, Failure>),
+    Solved(usize, Result<(Answer, Cost), Failure>),
+}
+
+fn solve_day(
+    solution: &Solution,
+    input: &str,
+    params: &Params,
+    problems: &[usize],
+    variant: &str,
+    mut emit: impl FnMut(Event),
+) {
+    let parsed = match parse(solution, input) {
+        Ok((parsed, cost)) => {
+            emit(Event::Parsed(Ok(cost)));
+            parsed
+        }
+        Err(e) => return emit(Event::Parsed(Err(e.into()))),
+    };
+
+    for &problem in problems {
+        let res = solve_variant(&*parsed, problem, variant, params).map_err(Failure::from);
+        emit(Event::Solved(problem, res));
+    }
+}
+
+// Turns the events of a `solve_day` call into one report per problem.
+struct Collector {
+    day: usize,
+    pending: Vec<usize>,
+    parse: Cost,
+    reports: Vec<Report>,
+}
+
+impl Collector {
+    fn new(day: usize, problems: &[usize]) -> Self {
+        Self {
+            day,
+            pending: problems.to_vec(),
+            parse: Cost::default(),
+            reports: Vec::with_capacity(problems.len()),
+        }
+    }
+
+    fn handle(&mut self, event: Event) {
+        match event {
+            Event::Parsed(Ok(cost)) => self.parse = cost,
+            Event::Parsed(Err(failure)) => self.fail_pending(failure),
+            Event::Solved(problem, res) => {
+                let parse = self.parse;
+                let outcome = res.map(|(answer, solve)| Solved {
+                    answer,
+                    parse: parse.duration,
+                    solve: solve.duration,
+                    alloc: parse.alloc.zip(solve.alloc).map(|(p, s)| p.then(&s)),
+                });
+                self.finish(problem, outcome);
+            }
+        }
+    }
+
+    fn finish(&mut self, problem: usize, outcome: Result<Solved, Failure>) {
+        self.pending.retain(|&x| x != problem);
+        self.reports.push(Report::new(self.day, problem, outcome));
+    }
+
+    fn fail_pending(&mut self, failure: Failure) {
+        for problem in std::mem::take(&mut self.pending) {
+            self.finish(problem, Err(failure.clone()));
+        }
+    }
+}
+
+// Normalizes an input the way `solution` wants it, returning what was fixed
+// along with it.
+pub fn prepare(solution: &Solution, input: &str) -> (String, Vec<Warning>) {
+    normalize::normalize(input, solution.trim_trailing_space)
+}
+
+// Calls `f`, turning a panic into a `Panic` error so that one broken solution
+// does not take down the others. The panic's location is only known if
+// `install_panic_hook` has been called.
+fn call<T>(f: impl FnOnce() -> Result<T>) -> Result<(T, Cost)> {
+    CAPTURING.with(|c| *c.borrow_mut() = Some(None));
+
+    let tracker = Tracker::start();
+    let start = Instant::now();
+    let res = panic::catch_unwind(AssertUnwindSafe(f));
+    let end = Instant::now();
+    let alloc = tracker.map(Tracker::finish);
+
+    let captured = CAPTURING.with(|c| c.borrow_mut().take()).flatten();
+
+    match res {
+        Ok(ans) => {
+            let cost = Cost {
+                duration: end.duration_since(start),
+                alloc,
+            };
+            Ok((ans?, cost))
+        }
+        Err(payload) => Err(captured
+            .unwrap_or_else(|| Panic {
+                message: panic_message(&*payload),
+                location: None,
+            })
+            .into()),
+    }
+}
+
+// A panic raised by a solution.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Panic {
+    pub message: String,
+    pub location: Option<String>,
+}
+
+impl std::fmt::Display for Panic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.location {
+            Some(location) => write!(f, "panicked at {}: {}", location, self.message),
+            None => write!(f, "panicked: {}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for Panic {}
+
+// A problem that did not finish within its time budget.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Timeout(pub Duration);
+
+impl std::fmt::Display for Timeout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "timed out after {:?}", self.0)
+    }
+}
+
+impl std::error::Error for Timeout {}
+
+thread_local! {
+    // Some while a solution is running on this thread. The panic hook stores
+    // the panic here instead of printing it.
+    static CAPTURING: RefCell<Option<Option<Panic>>> = const { RefCell::new(None) };
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "non-string panic payload".to_string())
+}
+
+// Wraps the current panic hook so that panics raised while a solution runs are
+// recorded, location included, rather than printed. Panics anywhere else are
+// handled as before. This replaces a process-wide hook, so it is left to
+// binaries to call once at startup.
+pub fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+
+    INSTALL.call_once(|| {
+        let previous_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let captured = CAPTURING.with(|c| match c.borrow_mut().as_mut() {
+                Some(slot) => {
+                    *slot = Some(Panic {
+                        message: panic_message(info.payload()),
+                        location: info.location().map(|l| l.to_string()),
+                    });
+                    true
+                }
+                None => false,
+            });
+
+            if !captured {
+                previous_hook(info);
+            }
+        }));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{inputs::Missing, report::Status};
+
+    #[test]
+    fn panic_test() {
+        install_panic_hook();
+        let err = call(|| -> Result<()> { panic!("oh no: {}", 42) }).unwrap_err();
+        let p = err.downcast_ref::<Panic>().unwrap();
+        assert_eq!(p.message, "oh no: 42");
+        assert!(p.location.as_ref().unwrap().starts_with("src/runner.rs:"));
+
+        // Panics outside of a solution are not captured.
+        assert!(CAPTURING.with(|c| c.borrow().is_none()));
+    }
+
+    #[test]
+    fn run_problems_test() {
+        let input = "L68\nR10\n";
+        let reports = run_problems(1, &[1, 2], Ok(input), &[], DEFAULT_VARIANT, None).reports;
+        let answers: Vec<_> = reports.iter().map(|r| r.answer().cloned()).collect();
+        assert_eq!(answers, [Some(0u64.into()), Some(1u64.into())]);
+
+        let overrides = [("start".to_string(), 68)];
+        let reports = run_problems(1, &[1], Ok(input), &overrides, DEFAULT_VARIANT, None).reports;
+        assert_eq!(reports[0].answer(), Some(&1u64.into()));
+
+        let overrides = [("n".to_string(), 10)];
+        let reports =
+            run_problems(1, &[1, 2], Ok(input), &overrides, DEFAULT_VARIANT, None).reports;
+        assert!(reports.iter().all(|r| r.status() == Status::Error));
+
+        let reports = run_problems(1, &[1, 2], Ok("bad"), &[], DEFAULT_VARIANT, None).reports;
+        assert!(reports.iter().all(|r| r.status() == Status::Error));
+
+        let reports = run_problems(1, &[1], Ok(input), &[], "nope", None).reports;
+        assert_eq!(reports[0].status(), Status::Error);
+
+        let run = run_problems(1, &[1], Ok("L68\r\nR10"), &[], DEFAULT_VARIANT, None);
+        assert_eq!(run.warnings, [Warning::CarriageReturns(1)]);
+        assert_eq!(run.reports[0].answer(), Some(&0u64.into()));
+
+        let input = "L68\nR10\n".repeat(10000);
+        let reports = run_problems(
+            1,
+            &[1, 2],
+            Ok(&input),
+            &[],
+            DEFAULT_VARIANT,
+            Some(Duration::from_nanos(1)),
+        )
+        .reports;
+        assert!(reports.iter().all(|r| r.status() == Status::Timeout));
+
+        let missing = anyhow!(Missing("day01.txt".into())).into();
+        let reports = run_problems(1, &[1, 2], Err(missing), &[], DEFAULT_VARIANT, None).reports;
+        assert!(reports.iter().all(|r| r.status() == Status::Missing));
+    }
+}
@@ -0,0 +1,166 @@
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+
+use adventofcode2025::examples;
+
+const TEMPLATE: &str = "src/solutions/day.rs.template";
+const REGISTRY: &str = "src/solutions.rs";
+
+// An example to save for the new day, which its generated test then checks.
+#[derive(Clone, Debug, Default)]
+pub struct Example {
+    pub input: Option<String>,
+    pub answers: [Option<String>; 2],
+}
+
+// Adds day `day` to the crate at `root`: creates its solution from the
+// template, registers it in the `days!` list, saves the example if there is one
+// and creates an empty input file in `inputs_dir` unless one is already there.
+pub fn new_day(root: &Path, inputs_dir: &Path, day: usize, example: &Example) -> Result<()> {
+    let name = format!("day{:02}", day);
+
+    let module = root.join("src/solutions").join(format!("{}.rs", name));
+    if module.exists() {
+        bail!("{} already exists", module.display());
+    }
+
+    let registry_path = root.join(REGISTRY);
+    let registry = std::fs::read_to_string(&registry_path)
+        .with_context(|| format!("failed to read {}", registry_path.display()))?;
+    let registry = register(&registry, &name)?;
+
+    let template_path = root.join(TEMPLATE);
+    let template = std::fs::read_to_string(&template_path)
+        .with_context(|| format!("failed to read {}", template_path.display()))?;
+
+    std::fs::write(&module, template)
+        .with_context(|| format!("failed to write {}", module.display()))?;
+    println!("created {}", module.display());
+
+    std::fs::write(&registry_path, registry)
+        .with_context(|| format!("failed to write {}", registry_path.display()))?;
+    println!("registered {} in {}", name, registry_path.display());
+
+    if let Some(input) = &example.input {
+        let dir = root.join(examples::DEFAULT_DIR);
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("failed to create {}", dir.display()))?;
+
+        let path = dir.join(format!("{}-1.txt", name));
+        std::fs::write(&path, input)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        println!("created {}", path.display());
+
+        let path = path.with_extension("answers");
+        std::fs::write(&path, answers_file(&example.answers))
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        println!("created {}", path.display());
+    }
+
+    let input = inputs_dir.join(format!("{}.txt", name));
+    if !input.exists() {
+        std::fs::create_dir_all(inputs_dir)
+            .with_context(|| format!("failed to create {}", inputs_dir.display()))?;
+        std::fs::write(&input, "")
+            .with_context(|| format!("failed to write {}", input.display()))?;
+        println!("created {}", input.display());
+    }
+
+    Ok(())
+}
+
+// Adds `name` to the `days!(...)` invocation in `source`, keeping the list
+// sorted and laid out the way rustfmt would.
+fn register(source: &str, name: &str) -> Result<String> {
+    let start = source
+        .rfind("days!(")
+        .context("no days!(...) list in solutions.rs")?;
+    let len = source[start..]
+        .find(");")
+        .context("unterminated days!(...) list")?;
+    let list = &source[start + "days!(".len()..start + len];
+
+    let mut days: Vec<&str> = list
+        .split(',')
+        .map(|x| x.trim())
+        .filter(|x| !x.is_empty())
+        .collect();
+    if days.contains(&name) {
+        bail!("{} is already registered", name);
+    }
+    days.push(name);
+    days.sort_unstable();
+
+    let one_line = format!("days!({});", days.join(", "));
+    let formatted = if one_line.len() <= 60 {
+        one_line
+    } else {
+        let mut lines = vec![String::new()];
+        for day in days {
+            let line = lines.last_mut().unwrap();
+            if line.is_empty() {
+                line.push_str("    ");
+            } else if line.len() + 2 + day.len() + 1 > 100 {
+                line.push(',');
+                lines.push("    ".to_string());
+            } else {
+                line.push_str(", ");
+            }
+            lines.last_mut().unwrap().push_str(day);
+        }
+        format!("days!(\n{}\n);", lines.join("\n"))
+    };
+
+    Ok(format!(
+        "{}{}{}",
+        &source[..start],
+        formatted,
+        &source[start + len + 2..]
+    ))
+}
+
+// Lays out expected answers like the files in `puzzle-answers`.
+fn answers_file(answers: &[Option<String>; 2]) -> String {
+    let lines: Vec<_> = answers
+        .iter()
+        .map(|x| x.as_deref().unwrap_or("").trim())
+        .collect();
+    format!("{}\n", lines.join("\n").trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn register_test() {
+        let source = "mod x;\n\ndays!(day01, day03);\n";
+        assert_eq!(
+            register(source, "day02").unwrap(),
+            "mod x;\n\ndays!(day01, day02, day03);\n"
+        );
+        assert!(register(source, "day03").is_err());
+
+        let days: Vec<_> = (1..=12).map(|x| format!("day{:02}", x)).collect();
+        let source = format!("days!(\n    {}\n);\n", days.join(", "));
+        let registered = register(&source, "day13").unwrap();
+        assert_eq!(
+            registered,
+            format!("days!(\n    {}, day13\n);\n", days.join(", "))
+        );
+
+        let registered = register(&registered, "day14").unwrap();
+        assert!(registered.lines().all(|x| x.len() <= 100));
+        assert!(registered.ends_with(",\n    day14\n);\n"));
+    }
+
+    #[test]
+    fn answers_file_test() {
+        let answers = |a: Option<&str>, b: Option<&str>| [a.map(String::from), b.map(String::from)];
+        assert_eq!(answers_file(&answers(Some("3"), Some("6"))), "3\n6\n");
+        assert_eq!(answers_file(&answers(Some("3"), None)), "3\n");
+        assert_eq!(answers_file(&answers(None, Some("6"))), "\n6\n");
+        assert_eq!(answers_file(&answers(None, None)), "\n");
+    }
+}
@@ -0,0 +1,278 @@
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+
+use crate::{
+    bench, generate,
+    params::Params,
+    report::{Failure, Status},
+    runner,
+    solutions::DEFAULT_VARIANT,
+};
+
+#[derive(Clone, Copy, Debug)]
+pub struct Options {
+    pub start: usize,
+    // How much bigger each step's input is than the last one's.
+    pub factor: f64,
+    pub steps: usize,
+    // Stop growing once a step takes longer than this.
+    pub limit: Duration,
+    pub seed: u64,
+    pub samples: usize,
+}
+
+impl Options {
+    // The input size of each step, without repeats.
+    pub fn sizes(&self) -> Vec<usize> {
+        let mut sizes = Vec::with_capacity(self.steps);
+        let mut size = self.start.max(1) as f64;
+        for _ in 0..self.steps {
+            let n = size.round() as usize;
+            if sizes.last() != Some(&n) {
+                sizes.push(n);
+            }
+            size *= self.factor;
+        }
+        sizes
+    }
+}
+
+// The median times on one generated input.
+#[derive(Clone, Debug)]
+pub struct Step {
+    // The size given to the generator.
+    pub size: usize,
+    // The length of the generated input. Growth is fitted against this, as
+    // generators differ in what their size counts, like a grid's side.
+    pub bytes: usize,
+    pub parse: Result<Duration, Failure>,
+    pub problems: [Result<Duration, Failure>; 2],
+}
+
+impl Step {
+    // The parse time followed by each problem's.
+    pub fn times(&self) -> [&Result<Duration, Failure>; 3] {
+        [&self.parse, &self.problems[0], &self.problems[1]]
+    }
+
+    pub fn total(&self) -> Duration {
+        self.times().iter().filter_map(|x| x.as_ref().ok()).sum()
+    }
+}
+
+// Times the parse and both problems of `day` on a generated input of `size`.
+// The first run has `opts.limit` as its timeout, so a problem that is far too
+// slow is cut short. Only the problems that finished in time are sampled.
+pub fn measure(day: usize, params: &Params, size: usize, opts: &Options) -> Result<Step> {
+    let solution = runner::lookup(day)?;
+    let input = generate::generate(solution, opts.seed, size)?;
+    let reports = runner::solve_problems(
+        day,
+        solution,
+        &[1, 2],
+        input.as_str().into(),
+        params,
+        DEFAULT_VARIANT,
+        Some(opts.limit),
+    );
+
+    let sample_opts = bench::Options {
+        warmup: 0,
+        samples: opts.samples,
+        budget: None,
+        precision: 0.0,
+    };
+    let mut step = Step {
+        size,
+        bytes: input.len(),
+        // Without a finished problem there's nothing to show the parse
+        // finished either, so it takes the first problem's failure.
+        parse: match reports.iter().find(|r| r.outcome.is_ok()) {
+            Some(_) => Ok(Duration::ZERO),
+            None => Err(reports[0].outcome.clone().unwrap_err()),
+        },
+        problems: std::array::from_fn(|i| reports[i].outcome.clone().map(|_| Duration::ZERO)),
+    };
+    if step.parse.is_err() {
+        return Ok(step);
+    }
+
+    let parse = bench::sample(&sample_opts, || {
+        Ok(runner::parse(solution, &input)?.1.duration)
+    });
+    step.parse = parse.map(|x| x.median).map_err(Failure::from);
+    let (parsed, _) = runner::parse(solution, &input)?;
+    for (i, problem) in step.problems.iter_mut().enumerate() {
+        if problem.is_err() {
+            continue;
+        }
+        let stats = bench::sample(&sample_opts, || {
+            Ok(runner::solve(&*parsed, i + 1, params)?.1.duration)
+        });
+        *problem = stats.map(|x| x.median).map_err(Failure::from);
+    }
+
+    Ok(step)
+}
+
+// Measures steps of growing size until one takes longer than `opts.limit`,
+// passing each to `on_step` as soon as it is measured.
+pub fn run(
+    day: usize,
+    params: &Params,
+    opts: &Options,
+    mut on_step: impl FnMut(&Step),
+) -> Result<Vec<Step>> {
+    if opts.start == 0 || opts.factor.is_nan() || opts.factor <= 1.0 {
+        bail!("inputs have to start at size 1 or more and grow by a factor above 1");
+    }
+
+    let mut steps = Vec::new();
+    for size in opts.sizes() {
+        let step = measure(day, params, size, opts)?;
+        on_step(&step);
+
+        let timed_out = step
+            .times()
+            .iter()
+            .any(|x| x.as_ref().is_err_and(|f| f.status == Status::Timeout));
+        let total = step.total();
+        steps.push(step);
+        if timed_out || total > opts.limit {
+            break;
+        }
+    }
+    Ok(steps)
+}
+
+// Parses a `--factor`, which has to make each input bigger than the last.
+pub fn parse_factor(s: &str) -> Result<f64> {
+    let factor: f64 = s.parse().context("not a number")?;
+    if factor.is_nan() || factor <= 1.0 {
+        bail!("has to be more than 1");
+    }
+    Ok(factor)
+}
+
+// The exponent of the parse and of each problem, fitted against the input
+// length over the steps where it succeeded.
+pub fn fit(steps: &[Step]) -> [Option<f64>; 3] {
+    std::array::from_fn(|i| {
+        let points: Vec<_> = steps
+            .iter()
+            .filter_map(|s| Some((s.bytes, *s.times()[i].as_ref().ok()?)))
+            .collect();
+        fit_exponent(&points)
+    })
+}
+
+// Fits `time = c * n^k` by least squares on a log-log scale and returns `k`.
+// Needs at least two distinct `n`.
+pub fn fit_exponent(points: &[(usize, Duration)]) -> Option<f64> {
+    let logs: Vec<(f64, f64)> = points
+        .iter()
+        .filter(|(_, t)| !t.is_zero())
+        .map(|&(n, t)| ((n as f64).ln(), t.as_secs_f64().ln()))
+        .collect();
+    let n = logs.len() as f64;
+    let mean_x = logs.iter().map(|x| x.0).sum::<f64>() / n;
+    let mean_y = logs.iter().map(|x| x.1).sum::<f64>() / n;
+
+    let sxx: f64 = logs.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    let sxy: f64 = logs.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    if logs.len() < 2 || sxx == 0.0 {
+        return None;
+    }
+    Some(sxy / sxx)
+}
+
+// Names the growth rate nearest to `k`, if it is close to a whole power.
+pub fn describe(k: f64) -> Option<&'static str> {
+    let names = ["constant", "linear", "quadratic", "cubic"];
+    let nearest = k.round();
+    if (k - nearest).abs() > 0.25 || !(0.0..names.len() as f64).contains(&nearest) {
+        return None;
+    }
+    Some(names[nearest as usize])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sizes_test() {
+        let opts = Options {
+            start: 1,
+            factor: 1.5,
+            steps: 6,
+            limit: Duration::from_secs(1),
+            seed: 0,
+            samples: 1,
+        };
+        assert_eq!(opts.sizes(), [1, 2, 3, 5, 8]);
+    }
+
+    #[test]
+    fn measure_test() {
+        let opts = Options {
+            start: 16,
+            factor: 2.0,
+            steps: 1,
+            limit: Duration::from_secs(1),
+            seed: 0,
+            samples: 1,
+        };
+        let solution = runner::lookup(4).unwrap();
+        let params = Params::resolve(solution.params, &[]).unwrap();
+        // day04's size is the side of the grid, with a newline ending each row.
+        let step = measure(4, &params, 16, &opts).unwrap();
+        assert_eq!((step.size, step.bytes), (16, 16 * 17));
+        assert!(step.times().iter().all(|x| x.is_ok()));
+
+        let opts = Options { steps: 3, ..opts };
+        let mut sizes = Vec::new();
+        let steps = run(4, &params, &opts, |x| sizes.push(x.size)).unwrap();
+        assert_eq!(sizes, [16, 32, 64]);
+        assert!(fit(&steps).iter().all(|x| x.is_some()));
+
+        // A step that runs out of time is the last.
+        let opts = Options {
+            limit: Duration::from_nanos(1),
+            ..opts
+        };
+        let steps = run(4, &params, &opts, |_| {}).unwrap();
+        assert_eq!(steps.len(), 1);
+        assert_eq!(
+            steps[0].problems[0].as_ref().unwrap_err().status,
+            Status::Timeout
+        );
+
+        let shrinking = Options {
+            factor: 0.5,
+            ..opts
+        };
+        assert!(run(4, &params, &shrinking, |_| {}).is_err());
+        let empty = Options { start: 0, ..opts };
+        assert!(run(4, &params, &empty, |_| {}).is_err());
+        assert!(parse_factor("1.5").is_ok());
+        assert!(parse_factor("1").is_err());
+        assert!(parse_factor("NaN").is_err());
+    }
+
+    #[test]
+    fn fit_exponent_test() {
+        let quadratic: Vec<_> = [10, 20, 40, 80]
+            .into_iter()
+            .map(|n| (n, Duration::from_nanos(3 * n as u64 * n as u64)))
+            .collect();
+        let k = fit_exponent(&quadratic).unwrap();
+        assert!((k - 2.0).abs() < 1e-6, "{}", k);
+        assert_eq!(describe(k), Some("quadratic"));
+
+        assert_eq!(fit_exponent(&quadratic[..1]), None);
+        assert_eq!(describe(1.5), None);
+        assert_eq!(describe(0.9), Some("linear"));
+    }
+}
@@ -0,0 +1,114 @@
+use std::str::FromStr;
+
+use anyhow::{Context, Result, anyhow, bail};
+
+use adventofcode2025::util::Range;
+
+// Which days and parts of the registry to run.
+#[derive(clap::Args, Clone, Debug, Default)]
+pub struct Selection {
+    /// Only run these days, e.g. "1-5,9"
+    #[arg(long)]
+    days: Option<DaySet>,
+    /// Skip these days, e.g. "8,10"
+    #[arg(long)]
+    exclude: Option<DaySet>,
+    /// Only run this part
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+}
+
+impl Selection {
+    // The selected days out of `all`, in order.
+    pub fn days(&self, all: impl IntoIterator<Item = usize>) -> Result<Vec<usize>> {
+        let mut days: Vec<usize> = all
+            .into_iter()
+            .filter(|&d| self.days.as_ref().is_none_or(|x| x.contains(d)))
+            .filter(|&d| !self.exclude.as_ref().is_some_and(|x| x.contains(d)))
+            .collect();
+        days.sort_unstable();
+
+        if days.is_empty() {
+            bail!("no days selected");
+        }
+        Ok(days)
+    }
+
+    pub fn parts(&self) -> Vec<usize> {
+        match self.part {
+            Some(part) => vec![part as usize],
+            None => vec![1, 2],
+        }
+    }
+}
+
+// A set of days written as a comma separated list of days and inclusive
+// ranges, like "1-5,9".
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DaySet(Vec<Range>);
+
+impl DaySet {
+    pub fn contains(&self, day: usize) -> bool {
+        self.0.iter().any(|r| r.contains(day as u64))
+    }
+}
+
+impl FromStr for DaySet {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let parse_day = |x: &str| -> Result<u64> {
+            x.trim()
+                .parse()
+                .with_context(|| format!("bad day: {:?}", x.trim()))
+        };
+
+        s.split(',')
+            .map(|part| {
+                let (start, end) = match part.split_once('-') {
+                    Some((start, end)) => (parse_day(start)?, parse_day(end)?),
+                    None => {
+                        let day = parse_day(part)?;
+                        (day, day)
+                    }
+                };
+                if start > end {
+                    return Err(anyhow!("empty range: {}", part.trim()));
+                }
+                Ok(Range::new(start, end + 1))
+            })
+            .collect::<Result<_>>()
+            .map(DaySet)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn day_set_test() {
+        let set: DaySet = "1-5, 9".parse().unwrap();
+        let days: Vec<_> = (0..12).filter(|&d| set.contains(d)).collect();
+        assert_eq!(days, [1, 2, 3, 4, 5, 9]);
+
+        assert!("".parse::<DaySet>().is_err());
+        assert!("3-1".parse::<DaySet>().is_err());
+        assert!("1,x".parse::<DaySet>().is_err());
+    }
+
+    #[test]
+    fn selection_test() {
+        let sel = Selection {
+            days: Some("1-5,9".parse().unwrap()),
+            exclude: Some("2,4".parse().unwrap()),
+            part: Some(2),
+        };
+        assert_eq!(sel.days([9, 1, 2, 3, 4, 5, 6]).unwrap(), [1, 3, 5, 9]);
+        assert_eq!(sel.parts(), [2]);
+        assert!(sel.days([6, 7]).is_err());
+
+        assert_eq!(Selection::default().days([2, 1]).unwrap(), [1, 2]);
+        assert_eq!(Selection::default().parts(), [1, 2]);
+    }
+}
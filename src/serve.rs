@@ -0,0 +1,708 @@
+use std::{
+    ffi::OsString,
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    process::{Command, Stdio},
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    time::Duration,
+};
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    params::Param,
+    report::{Report, Status},
+    runner::{self, Timeout},
+    solutions::{self, DEFAULT_VARIANT},
+};
+
+// Inputs are a few tens of kilobytes, so anything much bigger is a mistake.
+const MAX_BODY: usize = 16 << 20;
+
+// How long a client may leave a read or write of its connection waiting.
+const IO_TIMEOUT: Duration = Duration::from_secs(30);
+
+// The longest request line or header, and the most headers, a request may
+// have. Real clients send a handful of short ones.
+const MAX_LINE: usize = 8 << 10;
+const MAX_HEADERS: usize = 100;
+
+#[derive(Clone, Debug)]
+pub struct Options {
+    // How long each solve may take.
+    pub timeout: Duration,
+    // Connections beyond this many at once are turned away with a 503.
+    pub max_connections: usize,
+    // The origin, or "*", that browsers may send requests from. No CORS
+    // headers are sent without one.
+    pub allow_origin: Option<String>,
+    // The command line of a process that calls `work`, to solve each request
+    // in. Without one, solves run on a thread of the server, where a stack
+    // overflow takes the server down and a solve that times out keeps running.
+    pub worker: Option<Vec<OsString>>,
+    // Reports errors that don't belong to any one request, like a failed
+    // accept.
+    pub log: fn(&str),
+}
+
+// Serves the solutions over HTTP, answering each connection on its own thread:
+//
+//   GET /days                 lists the days with their parameters and variants
+//   POST /day/{n}/part/{p}    solves part `p` of day `n` with the body as input
+//
+// Solving takes the variant and parameter values as query arguments, as in
+// `/day/8/part/1?n=10&variant=default`. Responses are JSON. A solved part
+// comes back as the same record `run --format json` prints, plus any warnings
+// about the input, with a 422 status if it failed.
+pub fn serve(listener: TcpListener, opts: Options) -> Result<()> {
+    let opts = Arc::new(opts);
+    let active = Arc::new(AtomicUsize::new(0));
+    for stream in listener.incoming() {
+        let mut stream = match stream {
+            Ok(x) => x,
+            Err(e) => {
+                (opts.log)(&format!("failed to accept connection: {}", e));
+                // Running out of file descriptors fails every accept until a
+                // connection closes, so don't spin on it.
+                std::thread::sleep(Duration::from_millis(10));
+                continue;
+            }
+        };
+        let slot = Slot::take(&active);
+        if slot.count > opts.max_connections {
+            // Answered here so the client isn't left waiting for a thread.
+            let _ = stream.set_write_timeout(Some(IO_TIMEOUT));
+            let response = Response::error(503, "too many connections");
+            let _ = response.write(&mut stream, opts.allow_origin.as_deref());
+            continue;
+        }
+
+        let opts = opts.clone();
+        std::thread::spawn(move || {
+            let _slot = slot;
+            // The client hung up or sent garbage, which only affects it.
+            let _ = handle(stream, &opts);
+        });
+    }
+    Ok(())
+}
+
+// One of the open connections, counted until it is dropped.
+struct Slot {
+    active: Arc<AtomicUsize>,
+    // How many connections are open, this one included.
+    count: usize,
+}
+
+impl Slot {
+    fn take(active: &Arc<AtomicUsize>) -> Self {
+        Self {
+            active: active.clone(),
+            count: active.fetch_add(1, Ordering::SeqCst) + 1,
+        }
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.active.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+fn handle(stream: TcpStream, opts: &Options) -> Result<()> {
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+    let mut reader = BufReader::new(stream);
+    let response = match read_request(&mut reader) {
+        Ok(request) => route(&request, opts),
+        Err(e) if e.is::<HeadTooLarge>() => Response::error(431, format!("{:#}", e)),
+        Err(e) => Response::error(400, format!("{:#}", e)),
+    };
+    response.write(reader.get_mut(), opts.allow_origin.as_deref())
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    // The query string without its leading `?`.
+    pub query: String,
+    pub body: Vec<u8>,
+}
+
+// A request line or headers longer than the limits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct HeadTooLarge;
+
+impl std::fmt::Display for HeadTooLarge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "request lines are limited to {} bytes and headers to {}",
+            MAX_LINE, MAX_HEADERS
+        )
+    }
+}
+
+impl std::error::Error for HeadTooLarge {}
+
+// Reads a line of the request head, without letting the client make it
+// arbitrarily long.
+fn read_line(reader: &mut BufReader<TcpStream>) -> Result<String> {
+    let mut line = String::new();
+    let len = reader.take(MAX_LINE as u64).read_line(&mut line)?;
+    if len == MAX_LINE && !line.ends_with('\n') {
+        bail!(HeadTooLarge);
+    }
+    Ok(line)
+}
+
+fn read_request(reader: &mut BufReader<TcpStream>) -> Result<Request> {
+    let line = read_line(reader)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        bail!("malformed request line: {:?}", line.trim_end());
+    };
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let mut request = Request {
+        method: method.to_string(),
+        path: path.to_string(),
+        query: query.to_string(),
+        body: Vec::new(),
+    };
+
+    let mut len = 0;
+    let mut expect_continue = false;
+    for i in 0.. {
+        if i == MAX_HEADERS {
+            bail!(HeadTooLarge);
+        }
+        let line = read_line(reader)?;
+        if line.is_empty() {
+            bail!("connection closed in the headers");
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        let Some((name, value)) = line.split_once(':') else {
+            bail!("malformed header: {:?}", line);
+        };
+        match name.trim().to_ascii_lowercase().as_str() {
+            "content-length" => len = value.trim().parse().context("bad content-length")?,
+            "expect" => expect_continue = value.trim().eq_ignore_ascii_case("100-continue"),
+            _ => {}
+        }
+    }
+
+    if len > MAX_BODY {
+        bail!("body of {} bytes is too big", len);
+    }
+    // curl waits a second for this before sending a large body.
+    if expect_continue {
+        reader
+            .get_mut()
+            .write_all(b"HTTP/1.1 100 Continue\r\n\r\n")?;
+    }
+    request.body = vec![0; len];
+    reader.read_exact(&mut request.body)?;
+    Ok(request)
+}
+
+pub fn route(request: &Request, opts: &Options) -> Response {
+    let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+    match (request.method.as_str(), segments.as_slice()) {
+        // A browser's preflight, before it sends a request from another
+        // origin.
+        ("OPTIONS", _) if opts.allow_origin.is_some() => Response {
+            status: 204,
+            body: String::new(),
+        },
+        ("GET", ["days"]) => Response::json(200, &days()),
+        ("POST", ["day", day, "part", part]) => match (day.parse(), part.parse()) {
+            (Ok(day), Ok(part)) => solve(day, part, &request.query, &request.body, opts),
+            _ => Response::error(404, format!("no such page: {}", request.path)),
+        },
+        (_, ["days"] | ["day", _, "part", _]) => {
+            Response::error(405, format!("{} is not allowed here", request.method))
+        }
+        _ => Response::error(404, format!("no such page: {}", request.path)),
+    }
+}
+
+#[derive(Serialize)]
+struct DayInfo {
+    day: usize,
+    name: &'static str,
+    params: &'static [Param],
+    // The variants of each part, starting with the default.
+    variants: [Vec<&'static str>; 2],
+}
+
+fn days() -> Vec<DayInfo> {
+    runner::days()
+        .into_iter()
+        .map(|day| {
+            let solution = solutions::SOLUTIONS[&day];
+            DayInfo {
+                day,
+                name: solution.day,
+                params: solution.params,
+                variants: [(solution.variants)(1), (solution.variants)(2)],
+            }
+        })
+        .collect()
+}
+
+fn solve(day: usize, part: usize, query: &str, body: &[u8], opts: &Options) -> Response {
+    if runner::lookup(day).is_err() || !(1..=2).contains(&part) {
+        return Response::error(404, format!("no such puzzle: day {} part {}", day, part));
+    }
+    let Ok(input) = std::str::from_utf8(body) else {
+        return Response::error(400, "input is not UTF-8");
+    };
+
+    let mut job = Job {
+        day,
+        part,
+        variant: DEFAULT_VARIANT.to_string(),
+        overrides: Vec::new(),
+        input: input.to_string(),
+    };
+    for pair in query.split('&').filter(|x| !x.is_empty()) {
+        let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let (Ok(name), Ok(value)) = (percent_decode(name), percent_decode(value)) else {
+            return Response::error(400, format!("bad escape in query argument {:?}", pair));
+        };
+        if name == "variant" {
+            job.variant = value;
+            continue;
+        }
+        match value.parse() {
+            Ok(value) => job.overrides.push((name, value)),
+            Err(_) => return Response::error(400, format!("bad value for {}: {:?}", name, value)),
+        }
+    }
+
+    match &opts.worker {
+        Some(worker) => job
+            .run_in(worker, opts.timeout)
+            .unwrap_or_else(|e| job.fail(e)),
+        None => job.run(Some(opts.timeout)),
+    }
+}
+
+// Decodes a query name or value, where `+` is a space and `%XX` a byte.
+fn percent_decode(s: &str) -> Result<String> {
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut iter = s.bytes();
+    while let Some(b) = iter.next() {
+        bytes.push(match b {
+            b'+' => b' ',
+            b'%' => {
+                let hex = [iter.next(), iter.next()];
+                let hex = hex.map(|x| x.and_then(|x| (x as char).to_digit(16)));
+                let [Some(hi), Some(lo)] = hex else {
+                    bail!("% isn't followed by two hex digits");
+                };
+                (hi * 16 + lo) as u8
+            }
+            _ => b,
+        });
+    }
+    String::from_utf8(bytes).context("not UTF-8")
+}
+
+// A part to solve, as sent to a worker.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Job {
+    day: usize,
+    part: usize,
+    variant: String,
+    overrides: Vec<(String, i64)>,
+    input: String,
+}
+
+impl Job {
+    fn run(&self, timeout: Option<Duration>) -> Response {
+        let run = runner::run_problems(
+            self.day,
+            &[self.part],
+            Ok(&self.input),
+            &self.overrides,
+            &self.variant,
+            timeout,
+        );
+        let warnings = run.warnings.iter().map(|x| x.to_string()).collect();
+        Self::respond(&run.reports[0], warnings)
+    }
+
+    // Runs the job in a new process of the `worker` command, and kills it if
+    // it takes longer than `timeout`.
+    fn run_in(&self, worker: &[OsString], timeout: Duration) -> Result<Response> {
+        let Some((program, args)) = worker.split_first() else {
+            bail!("the worker command is empty");
+        };
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .context("failed to start a worker")?;
+
+        // Talk to the worker on another thread, so that this one can give up
+        // on it at the deadline.
+        let job = serde_json::to_vec(self)?;
+        let (mut stdin, mut stdout) = (child.stdin.take().unwrap(), child.stdout.take().unwrap());
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let res = stdin.write_all(&job).and_then(|_| {
+                drop(stdin);
+                let mut out = Vec::new();
+                stdout.read_to_end(&mut out).map(|_| out)
+            });
+            // The receiver is gone if we timed out, which is fine.
+            let _ = tx.send(res);
+        });
+
+        let out = match rx.recv_timeout(timeout) {
+            Ok(out) => out,
+            Err(_) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(anyhow!(Timeout(timeout)));
+            }
+        };
+        let status = child.wait()?;
+        if !status.success() {
+            bail!("worker died: {}", status);
+        }
+        let out = out.context("failed to talk to the worker")?;
+        serde_json::from_slice(&out).context("worker sent a bad response")
+    }
+
+    // The response to the job failing with `e`.
+    fn fail(&self, e: anyhow::Error) -> Response {
+        let report = Report::new(self.day, self.part, Err(e.into()));
+        Self::respond(&report, Vec::new())
+    }
+
+    fn respond(report: &Report, warnings: Vec<String>) -> Response {
+        let status = match report.status() {
+            Status::Ok => 200,
+            _ => 422,
+        };
+
+        #[derive(Serialize)]
+        struct Solved<'a> {
+            #[serde(flatten)]
+            report: &'a Report,
+            warnings: Vec<String>,
+        }
+
+        Response::json(status, &Solved { report, warnings })
+    }
+}
+
+// The worker side of `Options::worker`: reads a job from stdin, solves it and
+// writes the response to stdout.
+pub fn work() -> Result<()> {
+    let job: Job =
+        serde_json::from_reader(std::io::stdin().lock()).context("failed to read the job")?;
+    let response = job.run(None);
+    serde_json::to_writer(std::io::stdout().lock(), &response)?;
+    Ok(())
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+impl Response {
+    fn json(status: u16, body: &impl Serialize) -> Self {
+        Self {
+            status,
+            body: serde_json::to_string(body).expect("responses serialize"),
+        }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        #[derive(Serialize)]
+        struct Error {
+            error: String,
+        }
+
+        Self::json(
+            status,
+            &Error {
+                error: message.into(),
+            },
+        )
+    }
+
+    fn write(&self, w: &mut impl Write, allow_origin: Option<&str>) -> Result<()> {
+        let reason = match self.status {
+            200 => "OK",
+            204 => "No Content",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            422 => "Unprocessable Entity",
+            431 => "Request Header Fields Too Large",
+            503 => "Service Unavailable",
+            _ => "Unknown",
+        };
+        let cors = match allow_origin {
+            Some(origin) => format!(
+                "Access-Control-Allow-Origin: {}\r\n\
+                 Access-Control-Allow-Methods: GET, POST, OPTIONS\r\n\
+                 Access-Control-Allow-Headers: Content-Type\r\n",
+                origin
+            ),
+            None => String::new(),
+        };
+        write!(
+            w,
+            "HTTP/1.1 {} {}\r\n\
+             Content-Type: application/json\r\n\
+             Content-Length: {}\r\n\
+             {}\
+             Connection: close\r\n\r\n{}",
+            self.status,
+            reason,
+            self.body.len(),
+            cors,
+            self.body
+        )?;
+        w.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options() -> Options {
+        Options {
+            timeout: Duration::from_secs(10),
+            max_connections: 4,
+            allow_origin: None,
+            worker: None,
+            log: |_| {},
+        }
+    }
+
+    fn start(opts: Options) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || serve(listener, opts));
+        url
+    }
+
+    fn json(res: Result<ureq::Response, ureq::Error>) -> (u16, serde_json::Value) {
+        let res = match res {
+            Ok(x) => x,
+            Err(ureq::Error::Status(_, x)) => x,
+            Err(e) => panic!("{}", e),
+        };
+        let status = res.status();
+        (
+            status,
+            serde_json::from_str(&res.into_string().unwrap()).unwrap(),
+        )
+    }
+
+    fn post(path: &str, body: &str, opts: &Options) -> (u16, serde_json::Value) {
+        let request = Request {
+            method: "POST".to_string(),
+            path: path.to_string(),
+            query: String::new(),
+            body: body.as_bytes().to_vec(),
+        };
+        let response = route(&request, opts);
+        (
+            response.status,
+            serde_json::from_str(&response.body).unwrap(),
+        )
+    }
+
+    #[test]
+    fn serve_test() {
+        let url = start(options());
+        let input = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n";
+
+        let res = ureq::get(&format!("{}/days", url)).call().unwrap();
+        assert_eq!(res.header("Access-Control-Allow-Origin"), None);
+        let (status, days) = json(Ok(res));
+        assert_eq!(status, 200);
+        assert_eq!(days[0]["name"], "day01");
+        assert_eq!(days[0]["params"][0]["name"], "size");
+        assert_eq!(days[1]["variants"][0][1], "arithmetic");
+
+        let post = |path: &str, body: &str| {
+            json(ureq::post(&format!("{}{}", url, path)).send_string(body))
+        };
+
+        let (status, res) = post("/day/1/part/2", input);
+        assert_eq!((status, res["status"].as_str()), (200, Some("OK")));
+        assert_eq!(res["answer"], 6);
+        assert!(res["duration_ns"].as_u64().is_some());
+        assert_eq!(res["warnings"], serde_json::json!([]));
+
+        let (_, res) = post("/day/1/part/2", &input.replace('\n', "\r\n"));
+        assert_eq!(res["answer"], 6);
+        assert_eq!(res["warnings"][0], "converted 10 CR line endings to LF");
+
+        let (_, res) = post("/day/1/part/1?start=68", input);
+        assert_eq!(res["answer"], 1);
+        let (_, res) = post("/day/1/part/1?st%61rt=6%38", input);
+        assert_eq!(res["answer"], 1);
+        let (status, _) = post("/day/1/part/1?start=%6", input);
+        assert_eq!(status, 400);
+
+        let (status, res) = post("/day/1/part/1", "X3\n");
+        assert_eq!((status, res["status"].as_str()), (422, Some("ERROR")));
+        let errors = res["errors"].as_array().unwrap();
+        assert!(
+            errors
+                .last()
+                .unwrap()
+                .as_str()
+                .unwrap()
+                .starts_with("line 1, column 1")
+        );
+
+        let (status, _) = post("/day/1/part/1?variant=nope", input);
+        assert_eq!(status, 422);
+        let (status, _) = post("/day/1/part/1?start=x", input);
+        assert_eq!(status, 400);
+        let (status, _) = post("/day/99/part/1", input);
+        assert_eq!(status, 404);
+        let (status, res) = json(ureq::get(&format!("{}/day/1/part/1", url)).call());
+        assert_eq!(status, 405);
+        assert!(res["error"].is_string());
+        let (status, _) = json(ureq::request("OPTIONS", &format!("{}/days", url)).call());
+        assert_eq!(status, 405);
+    }
+
+    #[test]
+    fn head_too_large_test() {
+        let url = start(options());
+        let send = |head: String| {
+            let mut stream = TcpStream::connect(url.trim_start_matches("http://")).unwrap();
+            stream.write_all(head.as_bytes()).unwrap();
+            let mut line = String::new();
+            BufReader::new(stream).read_line(&mut line).unwrap();
+            line
+        };
+
+        let long = format!("GET /days?{} HTTP/1.1\r\n\r\n", "a".repeat(MAX_LINE));
+        assert!(send(long).starts_with("HTTP/1.1 431 "));
+        let many = format!(
+            "GET /days HTTP/1.1\r\n{}\r\n",
+            "A: b\r\n".repeat(MAX_HEADERS)
+        );
+        assert!(send(many).starts_with("HTTP/1.1 431 "));
+        let fine = format!("GET /days HTTP/1.1\r\n{}\r\n", "A: b\r\n".repeat(10));
+        assert!(send(fine).starts_with("HTTP/1.1 200 "));
+
+        assert_eq!(percent_decode("a+b%2C%3d").unwrap(), "a b,=");
+        assert!(percent_decode("%zz").is_err());
+        assert!(percent_decode("%ff").is_err());
+    }
+
+    #[test]
+    fn allow_origin_test() {
+        let url = start(Options {
+            allow_origin: Some("http://localhost:8000".to_string()),
+            ..options()
+        });
+
+        let res = ureq::get(&format!("{}/days", url)).call().unwrap();
+        assert_eq!(
+            res.header("Access-Control-Allow-Origin"),
+            Some("http://localhost:8000")
+        );
+        let res = ureq::request("OPTIONS", &format!("{}/day/1/part/1", url))
+            .call()
+            .unwrap();
+        assert_eq!(res.status(), 204);
+    }
+
+    #[test]
+    fn max_connections_test() {
+        let url = start(Options {
+            max_connections: 1,
+            ..options()
+        });
+
+        // Holds the only connection by never finishing its request.
+        let held = TcpStream::connect(url.trim_start_matches("http://")).unwrap();
+        let (status, res) = json(ureq::get(&format!("{}/days", url)).call());
+        assert_eq!(status, 503);
+        assert_eq!(res["error"], "too many connections");
+
+        drop(held);
+        let ok = (0..100).any(|_| {
+            std::thread::sleep(Duration::from_millis(10));
+            ureq::get(&format!("{}/days", url)).call().is_ok()
+        });
+        assert!(ok, "the connection was never given back");
+    }
+
+    #[test]
+    fn timeout_test() {
+        let opts = Options {
+            timeout: Duration::from_nanos(1),
+            ..options()
+        };
+        let input = "L68\nR10\n".repeat(10000);
+        let (status, res) = post("/day/1/part/2", &input, &opts);
+        assert_eq!((status, res["status"].as_str()), (422, Some("TIMEOUT")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn worker_test() {
+        let sh = |script: &str| Some(vec!["sh".into(), "-c".into(), script.into()]);
+
+        let opts = Options {
+            worker: sh("kill -SEGV $$"),
+            ..options()
+        };
+        let (status, res) = post("/day/1/part/1", "L68\n", &opts);
+        assert_eq!((status, res["status"].as_str()), (422, Some("ERROR")));
+        assert!(
+            res["errors"][0]
+                .as_str()
+                .unwrap()
+                .starts_with("worker died")
+        );
+
+        let opts = Options {
+            timeout: Duration::from_millis(100),
+            worker: sh("sleep 10"),
+            ..options()
+        };
+        let (status, res) = post("/day/1/part/1", "L68\n", &opts);
+        assert_eq!((status, res["status"].as_str()), (422, Some("TIMEOUT")));
+
+        let response = r#"{"status":200,"body":"{}"}"#;
+        let opts = Options {
+            worker: sh(&format!("cat > /dev/null; echo '{}'", response)),
+            ..options()
+        };
+        assert_eq!(
+            post("/day/1/part/1", "L68\n", &opts),
+            (200, serde_json::json!({}))
+        );
+    }
+}
@@ -1,5 +1,11 @@
 use std::collections::HashMap;
 
+use crate::{
+    answer::Answer,
+    generate::GenerateFn,
+    params::{Param, Params},
+};
+
 macro_rules! days {
     ($($x:ident), *) => {
         $(
@@ -10,17 +16,49 @@ macro_rules! days {
             $(
                 Solution {
                     day: stringify!($x),
-                    problem1: $x::problem1,
-                    problem2: $x::problem2,
-                    input: include_str!(concat!("../puzzle-inputs/", stringify!($x), ".txt"))
+                    parse: parse::<$x::Puzzle>,
+                    params: <$x::Puzzle as Day>::PARAMS,
+                    generate: <$x::Puzzle as Day>::GENERATE,
+                    oracles: has_oracles::<$x::Puzzle>(),
+                    variants: variant_names::<$x::Puzzle>,
+                    trim_trailing_space: <$x::Puzzle as Day>::TRIM_TRAILING_SPACE,
+                    input: embedded_input!($x),
                 },
             )*
         ];
 
+        // One test per day, checking it against its example files.
+        #[cfg(test)]
+        mod example_tests {
+            $(
+                #[test]
+                fn $x() {
+                    crate::examples::check(stringify!($x));
+                }
+            )*
+        }
     };
     ($($x:ident,) *) => (days!($($x),*););
 }
 
+#[cfg(feature = "embedded-inputs")]
+macro_rules! embedded_input {
+    ($x:ident) => {
+        Some(include_str!(concat!(
+            "../puzzle-inputs/",
+            stringify!($x),
+            ".txt"
+        )))
+    };
+}
+
+#[cfg(not(feature = "embedded-inputs"))]
+macro_rules! embedded_input {
+    ($x:ident) => {
+        None
+    };
+}
+
 lazy_static! {
     pub static ref SOLUTIONS: HashMap<usize, &'static Solution> =
         HashMap::from_iter(SOLUTIONS_ARR.iter().map(|x| (mod_name_to_num(x.day), x)));
@@ -33,13 +71,131 @@ fn mod_name_to_num(name: &str) -> usize {
         .fold(0, |acc, x| acc * 10 + x as usize)
 }
 
-pub type ProblemFn = fn(&str) -> Result<String, anyhow::Error>;
+// A day's solution. The input is parsed once into `Input`, which both problems
+// then share, so that parsing and solving can be timed separately.
+//
+// Numbers that differ between the example and the real puzzle are declared in
+// `PARAMS` and read from `params`, so they can be changed with `--param`.
+//
+// Days that can make up random inputs for stress testing set `GENERATE`. Slow
+// but obviously correct versions of the problems go in `ORACLES`, for
+// `crosscheck` to compare the real ones against.
+//
+// Other implementations of a problem, e.g. to compare approaches in `bench`,
+// are registered in `VARIANTS`. `problem1` and `problem2` are the variant
+// called "default".
+//
+// Inputs are normalized before parsing, which includes trimming trailing
+// spaces from each line. Days where those matter turn that off with
+// `TRIM_TRAILING_SPACE`.
+pub trait Day: Sized + 'static {
+    type Input<'a>;
+
+    const PARAMS: &'static [Param] = &[];
+    const GENERATE: Option<GenerateFn> = None;
+    const ORACLES: [Option<ProblemFn<Self>>; 2] = [None, None];
+    const VARIANTS: [&'static [Variant<Self>]; 2] = [&[], &[]];
+    const TRIM_TRAILING_SPACE: bool = true;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, anyhow::Error>;
+    fn problem1(data: &Self::Input<'_>, params: &Params) -> Result<Answer, anyhow::Error>;
+    fn problem2(data: &Self::Input<'_>, params: &Params) -> Result<Answer, anyhow::Error>;
+}
+
+pub type ProblemFn<D> =
+    for<'a, 'b> fn(&'b <D as Day>::Input<'a>, &Params) -> Result<Answer, anyhow::Error>;
+
+pub const DEFAULT_VARIANT: &str = "default";
+
+// A named alternative implementation of a problem.
+pub struct Variant<D: Day> {
+    pub name: &'static str,
+    pub solve: ProblemFn<D>,
+}
+
+// The names of a problem's variants, starting with the default.
+fn variant_names<D: Day>(problem: usize) -> Vec<&'static str> {
+    match D::VARIANTS.get(problem.wrapping_sub(1)) {
+        Some(variants) => std::iter::once(DEFAULT_VARIANT)
+            .chain(variants.iter().map(|x| x.name))
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+const fn has_oracles<D: Day>() -> [bool; 2] {
+    [D::ORACLES[0].is_some(), D::ORACLES[1].is_some()]
+}
+
+// A parsed input with its day's type erased.
+pub trait Parsed {
+    fn solve(&self, problem: usize, params: &Params) -> Result<Answer, anyhow::Error>;
+    fn variant(&self, problem: usize, name: &str, params: &Params)
+    -> Result<Answer, anyhow::Error>;
+    fn oracle(&self, problem: usize, params: &Params) -> Result<Answer, anyhow::Error>;
+}
+
+struct ParsedInput<'a, D: Day>(D::Input<'a>);
+
+impl<D: Day> Parsed for ParsedInput<'_, D> {
+    fn solve(&self, problem: usize, params: &Params) -> Result<Answer, anyhow::Error> {
+        match problem {
+            1 => D::problem1(&self.0, params),
+            2 => D::problem2(&self.0, params),
+            _ => anyhow::bail!("unknown problem: {}", problem),
+        }
+    }
+
+    fn variant(
+        &self,
+        problem: usize,
+        name: &str,
+        params: &Params,
+    ) -> Result<Answer, anyhow::Error> {
+        if name == DEFAULT_VARIANT {
+            return self.solve(problem, params);
+        }
+        let Some(variants) = D::VARIANTS.get(problem.wrapping_sub(1)) else {
+            anyhow::bail!("unknown problem: {}", problem);
+        };
+        match variants.iter().find(|x| x.name == name) {
+            Some(variant) => (variant.solve)(&self.0, params),
+            None => anyhow::bail!(
+                "unknown variant {:?} of problem {}, expected one of: {}",
+                name,
+                problem,
+                variant_names::<D>(problem).join(", ")
+            ),
+        }
+    }
+
+    fn oracle(&self, problem: usize, params: &Params) -> Result<Answer, anyhow::Error> {
+        match D::ORACLES.get(problem.wrapping_sub(1)) {
+            Some(Some(oracle)) => oracle(&self.0, params),
+            Some(None) => anyhow::bail!("no oracle for problem {}", problem),
+            None => anyhow::bail!("unknown problem: {}", problem),
+        }
+    }
+}
+
+fn parse<'a, D: Day>(input: &'a str) -> Result<Box<dyn Parsed + 'a>, anyhow::Error> {
+    Ok(Box::new(ParsedInput::<D>(D::parse(input)?)))
+}
+
+pub type ParseFn = for<'a> fn(&'a str) -> Result<Box<dyn Parsed + 'a>, anyhow::Error>;
 
 pub struct Solution {
     pub day: &'static str,
-    pub problem1: ProblemFn,
-    pub problem2: ProblemFn,
-    pub input: &'static str,
+    pub parse: ParseFn,
+    pub params: &'static [Param],
+    pub generate: Option<GenerateFn>,
+    // Which problems have an oracle.
+    pub oracles: [bool; 2],
+    // The names of each problem's variants, starting with the default.
+    pub variants: fn(usize) -> Vec<&'static str>,
+    pub trim_trailing_space: bool,
+    // The input baked into the binary, if built with `embedded-inputs`.
+    pub input: Option<&'static str>,
 }
 
 #[macro_use]
@@ -47,13 +203,14 @@ mod prelude {
     #[allow(unused_imports)]
     pub use anyhow::{Context, bail};
 
+    pub use super::{Day, ProblemFn, Variant};
+    pub use crate::answer::Answer;
+    pub use crate::generate::GenerateFn;
+    pub use crate::params::{Param, Params};
+
     macro_rules! parse {
         ($input:expr) => {
-            <::std::result::Result<_, _> as ::nom::Finish<_, _, _>>::finish(
-                parser::parse($input).map_err(|x| x.to_owned()),
-            )
-            .context("failed to parse input")?
-            .1
+            crate::parser::run($input, parser::parse).context("failed to parse input")?
         };
     }
 }
@@ -2,62 +2,95 @@ use std::{cmp::Ordering, fmt::Display};
 
 use crate::solutions::prelude::*;
 
-pub fn problem1(input: &str) -> Result<String, anyhow::Error> {
-    let data = parse!(input);
+pub struct Puzzle;
+
+impl Day for Puzzle {
+    type Input<'a> = Vec<Rotation>;
+
+    const PARAMS: &'static [Param] = &[
+        Param {
+            name: "size",
+            default: 100,
+            help: "number of positions on the dial",
+        },
+        Param {
+            name: "start",
+            default: 50,
+            help: "position the dial starts at",
+        },
+    ];
+    const GENERATE: Option<GenerateFn> = Some(generator::generate);
+    const ORACLES: [Option<ProblemFn<Self>>; 2] = [
+        Some(|data, params| oracle::problem1(data, params)),
+        Some(|data, params| oracle::problem2(data, params)),
+    ];
+
+    fn parse(input: &str) -> Result<Vec<Rotation>, anyhow::Error> {
+        Ok(parse!(input))
+    }
 
-    let mut cur = 50;
-    let mut cnt = 0;
+    fn problem1(data: &Vec<Rotation>, params: &Params) -> Result<Answer, anyhow::Error> {
+        let (size, mut cur) = dial(params)?;
+        let mut cnt = 0;
 
-    for r in data {
-        cur = rotate(cur, r);
-        if cur == 0 {
-            cnt += 1;
+        for &r in data {
+            cur = rotate(cur, r, size);
+            if cur == 0 {
+                cnt += 1;
+            }
         }
-    }
 
-    Ok(cnt.to_string())
-}
+        Ok(cnt.into())
+    }
 
-pub fn problem2(input: &str) -> Result<String, anyhow::Error> {
-    let data = parse!(input);
+    fn problem2(data: &Vec<Rotation>, params: &Params) -> Result<Answer, anyhow::Error> {
+        let (size, mut cur) = dial(params)?;
+        let mut cnt = 0;
 
-    let mut cur = 50;
-    let mut cnt = 0;
+        for r in data {
+            let n = match r.dir {
+                Direction::Left => cur - r.dist,
+                Direction::Right => cur + r.dist,
+            };
 
-    for r in data {
-        let n = match r.dir {
-            Direction::Left => cur - r.dist,
-            Direction::Right => cur + r.dist,
-        };
+            cnt += match n.cmp(&0) {
+                Ordering::Less => n.abs() / size + if cur == 0 { 0 } else { 1 },
+                Ordering::Equal => 1,
+                Ordering::Greater => n / size,
+            };
 
-        cnt += match n.cmp(&0) {
-            Ordering::Less => n.abs() / 100 + if cur == 0 { 0 } else { 1 },
-            Ordering::Equal => 1,
-            Ordering::Greater => n / 100,
-        };
+            cur = n.rem_euclid(size);
+        }
 
-        cur = n.rem_euclid(100);
+        Ok(cnt.into())
     }
+}
 
-    Ok(cnt.to_string())
+fn dial(params: &Params) -> Result<(i32, i32), anyhow::Error> {
+    let size = i32::try_from(params.get("size")).context("dial size out of range")?;
+    let start = params.get("start");
+    if size <= 0 || !(0..size as i64).contains(&start) {
+        bail!("the dial needs a positive size and a start position on it");
+    }
+    Ok((size, start as i32))
 }
 
-fn rotate(cur: i32, rotation: Rotation) -> i32 {
+fn rotate(cur: i32, rotation: Rotation, size: i32) -> i32 {
     match rotation.dir {
         Direction::Left => cur - rotation.dist,
         Direction::Right => cur + rotation.dist,
     }
-    .rem_euclid(100)
+    .rem_euclid(size)
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum Direction {
+pub enum Direction {
     Left,
     Right,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-struct Rotation {
+pub struct Rotation {
     dir: Direction,
     dist: i32,
 }
@@ -93,28 +126,63 @@ mod parser {
     }
 }
 
-#[cfg(test)]
-mod tests {
+mod generator {
+    use rand::{Rng, rngs::StdRng};
+
     use super::*;
 
-    const EXAMPLE_INPUT: &str = "L68
-    L30
-    R48
-    L5
-    R60
-    L55
-    L1
-    L99
-    R14
-    L82";
-
-    #[test]
-    fn problem1_test() {
-        assert_eq!(problem1(EXAMPLE_INPUT).unwrap(), "3")
+    // `size` rotations.
+    pub fn generate(rng: &mut StdRng, size: usize) -> String {
+        (0..size)
+            .map(|_| {
+                let dir = if rng.random_bool(0.5) {
+                    Direction::Left
+                } else {
+                    Direction::Right
+                };
+                let dist = rng.random_range(1..1000);
+                format!("{}\n", Rotation { dir, dist })
+            })
+            .collect()
     }
+}
+
+// Turns the dial one click at a time.
+mod oracle {
+    use super::*;
+
+    pub fn problem1(data: &[Rotation], params: &Params) -> Result<Answer, anyhow::Error> {
+        count_zeros(data, params, false)
+    }
+
+    pub fn problem2(data: &[Rotation], params: &Params) -> Result<Answer, anyhow::Error> {
+        count_zeros(data, params, true)
+    }
+
+    fn count_zeros(
+        data: &[Rotation],
+        params: &Params,
+        every_click: bool,
+    ) -> Result<Answer, anyhow::Error> {
+        let (size, mut cur) = dial(params)?;
+        let mut cnt = 0;
+
+        for r in data {
+            let click = match r.dir {
+                Direction::Left => size - 1,
+                Direction::Right => 1,
+            };
+            for _ in 0..r.dist {
+                cur = (cur + click) % size;
+                if every_click && cur == 0 {
+                    cnt += 1;
+                }
+            }
+            if !every_click && cur == 0 {
+                cnt += 1;
+            }
+        }
 
-    #[test]
-    fn problem2_test() {
-        assert_eq!(problem2(EXAMPLE_INPUT).unwrap(), "6")
+        Ok(cnt.into())
     }
 }
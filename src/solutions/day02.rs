@@ -2,21 +2,41 @@ use crate::solutions::prelude::*;
 
 use crate::util::Range;
 
-pub fn problem1(input: &str) -> Result<String, anyhow::Error> {
-    solve(input, is_valid)
-}
+pub struct Puzzle;
+
+impl Day for Puzzle {
+    type Input<'a> = Vec<Range>;
+    const GENERATE: Option<GenerateFn> = Some(generator::generate);
+    const VARIANTS: [&'static [Variant<Self>]; 2] = [
+        &[Variant {
+            name: "arithmetic",
+            solve: |data, params| arithmetic::problem1(data, params),
+        }],
+        &[Variant {
+            name: "arithmetic",
+            solve: |data, params| arithmetic::problem2(data, params),
+        }],
+    ];
+
+    fn parse(input: &str) -> Result<Vec<Range>, anyhow::Error> {
+        Ok(parse!(input))
+    }
+
+    fn problem1(data: &Vec<Range>, _params: &Params) -> Result<Answer, anyhow::Error> {
+        solve(data, is_valid)
+    }
 
-pub fn problem2(input: &str) -> Result<String, anyhow::Error> {
-    solve(input, is_valid2)
+    fn problem2(data: &Vec<Range>, _params: &Params) -> Result<Answer, anyhow::Error> {
+        solve(data, is_valid2)
+    }
 }
 
-fn solve(input: &str, valid_fn: fn(u64) -> bool) -> Result<String, anyhow::Error> {
-    let data = parse!(input);
+fn solve(data: &[Range], valid_fn: fn(u64) -> bool) -> Result<Answer, anyhow::Error> {
     let ans: u64 = data
         .iter()
         .flat_map(|r| r.iter().filter(|&n| !valid_fn(n)))
         .sum();
-    Ok(ans.to_string())
+    Ok(ans.into())
 }
 
 fn is_valid(n: u64) -> bool {
@@ -44,6 +64,37 @@ fn is_valid2(n: u64) -> bool {
     true
 }
 
+// Checks IDs by divisibility instead of comparing digits. A `len` digit
+// number made of a `p` digit pattern repeated is the pattern times 10..010..01,
+// with `p - 1` zeros between the ones.
+mod arithmetic {
+    use super::*;
+
+    pub fn problem1(data: &[Range], _params: &Params) -> Result<Answer, anyhow::Error> {
+        solve(data, |n| {
+            let len = digits(n);
+            len % 2 == 1 || !repeats(n, len, len / 2)
+        })
+    }
+
+    pub fn problem2(data: &[Range], _params: &Params) -> Result<Answer, anyhow::Error> {
+        solve(data, |n| {
+            let len = digits(n);
+            !(1..=len / 2).any(|p| len.is_multiple_of(p) && repeats(n, len, p))
+        })
+    }
+
+    fn digits(n: u64) -> u32 {
+        n.checked_ilog10().unwrap_or(0) + 1
+    }
+
+    // Whether the `len` digits of `n` are a `p` digit pattern repeated.
+    fn repeats(n: u64, len: u32, p: u32) -> bool {
+        let multiplier = (10u128.pow(len) - 1) / (10u128.pow(p) - 1);
+        (n as u128).is_multiple_of(multiplier)
+    }
+}
+
 mod parser {
     use super::*;
     use crate::parser::prelude::*;
@@ -56,21 +107,20 @@ mod parser {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const EXAMPLE_INPUT: &str = "11-22,95-115,998-1012,1188511880-1188511890,222220-222224,
-    1698522-1698528,446443-446449,38593856-38593862,565653-565659,
-    824824821-824824827,2121212118-2121212124";
-
-    #[test]
-    fn problem1_test() {
-        assert_eq!(problem1(EXAMPLE_INPUT).unwrap(), "1227775554")
-    }
+mod generator {
+    use rand::{Rng, rngs::StdRng};
 
-    #[test]
-    fn problem2_test() {
-        assert_eq!(problem2(EXAMPLE_INPUT).unwrap(), "4174379265")
+    // `size` comma separated ranges of up to a thousand IDs, each up to ten
+    // digits long.
+    pub fn generate(rng: &mut StdRng, size: usize) -> String {
+        let ranges: Vec<_> = (0..size)
+            .map(|_| {
+                let digits = rng.random_range(1..=10);
+                let start = rng.random_range(10u64.pow(digits - 1)..10u64.pow(digits));
+                let end = start + rng.random_range(0..1000);
+                format!("{}-{}", start, end)
+            })
+            .collect();
+        ranges.join(",") + "\n"
     }
 }
@@ -1,22 +1,50 @@
 use crate::solutions::prelude::*;
 
-pub fn problem1(input: &str) -> Result<String, anyhow::Error> {
-    solve(input, 2)
-}
+pub struct Puzzle;
+
+impl Day for Puzzle {
+    type Input<'a> = Vec<Vec<u8>>;
+
+    const PARAMS: &'static [Param] = &[
+        Param {
+            name: "digits1",
+            default: 2,
+            help: "batteries turned on per bank in problem 1",
+        },
+        Param {
+            name: "digits2",
+            default: 12,
+            help: "batteries turned on per bank in problem 2",
+        },
+    ];
+    const GENERATE: Option<GenerateFn> = Some(generator::generate);
+
+    fn parse(input: &str) -> Result<Vec<Vec<u8>>, anyhow::Error> {
+        Ok(parse!(input))
+    }
 
-pub fn problem2(input: &str) -> Result<String, anyhow::Error> {
-    solve(input, 12)
+    fn problem1(batteries: &Vec<Vec<u8>>, params: &Params) -> Result<Answer, anyhow::Error> {
+        solve(batteries, params.get("digits1"))
+    }
+
+    fn problem2(batteries: &Vec<Vec<u8>>, params: &Params) -> Result<Answer, anyhow::Error> {
+        solve(batteries, params.get("digits2"))
+    }
 }
 
-fn solve(input: &str, n: usize) -> Result<String, anyhow::Error> {
-    let batteries = parse!(input);
+fn solve(batteries: &[Vec<u8>], n: i64) -> Result<Answer, anyhow::Error> {
+    let shortest = batteries.iter().map(|x| x.len()).min().unwrap_or(0);
+    if !(1..=shortest.min(19) as i64).contains(&n) {
+        bail!("can't turn on {} batteries in banks of {}", n, shortest);
+    }
+    let n = n as usize;
 
     let ans: u64 = batteries
         .iter()
         .map(|battery| largest_output_n_digits(battery, n))
         .sum();
 
-    Ok(ans.to_string())
+    Ok(ans.into())
 }
 
 fn largest_output_n_digits(digits: &[u8], n: usize) -> u64 {
@@ -54,29 +82,27 @@ mod parser {
     use crate::parser::prelude::*;
 
     pub fn parse(input: &str) -> IResult<&str, Vec<Vec<u8>>> {
-        let digit = one_of("0123456789").map(|c| c.to_digit(10).unwrap() as u8);
+        let digit = context("digit", one_of("0123456789"));
+        let digit = digit.map(|c| c.to_digit(10).unwrap() as u8);
         let line = ws_line(many1(digit));
         let lines = many1(line);
         ws_all_consuming(lines).parse_complete(input)
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const EXAMPLE_INPUT: &str = "987654321111111
-    811111111111119
-    234234234234278
-    818181911112111";
-
-    #[test]
-    fn problem1_test() {
-        assert_eq!(problem1(EXAMPLE_INPUT).unwrap(), "357")
-    }
-
-    #[test]
-    fn problem2_test() {
-        assert_eq!(problem2(EXAMPLE_INPUT).unwrap(), "3121910778619")
+mod generator {
+    use rand::{Rng, rngs::StdRng};
+
+    // `size` banks of 100 batteries, like the real input.
+    pub fn generate(rng: &mut StdRng, size: usize) -> String {
+        (0..size)
+            .map(|_| {
+                let mut bank: String = (0..100)
+                    .map(|_| char::from(b'0' + rng.random_range(1..=9)))
+                    .collect();
+                bank.push('\n');
+                bank
+            })
+            .collect()
     }
 }
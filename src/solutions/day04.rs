@@ -2,31 +2,41 @@ use crate::solutions::prelude::*;
 
 use crate::util::grid::{Grid, Point};
 
-pub fn problem1(input: &str) -> Result<String, anyhow::Error> {
-    let grid = parse!(input);
-    let movable = movable_cells(&grid);
+pub struct Puzzle;
 
-    Ok(movable.len().to_string())
-}
+impl Day for Puzzle {
+    type Input<'a> = Grid<Cell>;
+    const GENERATE: Option<GenerateFn> = Some(generator::generate);
+
+    fn parse(input: &str) -> Result<Grid<Cell>, anyhow::Error> {
+        Ok(parse!(input))
+    }
 
-pub fn problem2(input: &str) -> Result<String, anyhow::Error> {
-    let mut grid = parse!(input);
-    let mut removed = 0;
+    fn problem1(grid: &Grid<Cell>, _params: &Params) -> Result<Answer, anyhow::Error> {
+        let movable = movable_cells(grid);
 
-    loop {
-        let movable = movable_cells(&grid);
-        if movable.is_empty() {
-            break;
-        }
+        Ok(movable.len().into())
+    }
+
+    fn problem2(grid: &Grid<Cell>, _params: &Params) -> Result<Answer, anyhow::Error> {
+        let mut grid = grid.clone();
+        let mut removed = 0;
+
+        loop {
+            let movable = movable_cells(&grid);
+            if movable.is_empty() {
+                break;
+            }
 
-        removed += movable.len();
+            removed += movable.len();
 
-        for loc in movable {
-            *grid.get_mut(loc).unwrap() = Cell::Empty;
+            for loc in movable {
+                *grid.get_mut(loc).unwrap() = Cell::Empty;
+            }
         }
-    }
 
-    Ok(removed.to_string())
+        Ok(removed.into())
+    }
 }
 
 fn movable_cells(grid: &Grid<Cell>) -> Vec<Point> {
@@ -48,7 +58,7 @@ fn movable_cells(grid: &Grid<Cell>) -> Vec<Point> {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum Cell {
+pub enum Cell {
     Empty,
     Occupied,
 }
@@ -68,28 +78,19 @@ mod parser {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const EXAMPLE_INPUT: &str = "..@@.@@@@.
-    @@@.@.@.@@
-    @@@@@.@.@@
-    @.@@@@..@.
-    @@.@@@@.@@
-    .@@@@@@@.@
-    .@.@.@.@@@
-    @.@@@.@@@@
-    .@@@@@@@@.
-    @.@.@@@.@.";
-
-    #[test]
-    fn problem1_test() {
-        assert_eq!(problem1(EXAMPLE_INPUT).unwrap(), "13")
-    }
-
-    #[test]
-    fn problem2_test() {
-        assert_eq!(problem2(EXAMPLE_INPUT).unwrap(), "43")
+mod generator {
+    use rand::{Rng, rngs::StdRng};
+
+    // A `size` by `size` grid, with rolls on about 60% of the cells.
+    pub fn generate(rng: &mut StdRng, size: usize) -> String {
+        (0..size)
+            .map(|_| {
+                let mut row: String = (0..size)
+                    .map(|_| if rng.random_bool(0.6) { '@' } else { '.' })
+                    .collect();
+                row.push('\n');
+                row
+            })
+            .collect()
     }
 }
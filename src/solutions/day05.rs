@@ -2,59 +2,108 @@ use crate::solutions::prelude::*;
 
 use crate::util::{Range, RangeSet};
 
-pub fn problem1(input: &str) -> Result<String, anyhow::Error> {
-    let (ranges, ingredients) = parse!(input);
-    let set = RangeSet::from(ranges);
-    let ans: usize = ingredients
-        .iter()
-        .filter(|&ingredient| set.contains(*ingredient))
-        .count();
-    Ok(ans.to_string())
+pub struct Puzzle;
+
+impl Day for Puzzle {
+    type Input<'a> = Inventory;
+
+    const GENERATE: Option<GenerateFn> = Some(generator::generate);
+    const ORACLES: [Option<ProblemFn<Self>>; 2] = [Some(oracle::problem1), Some(oracle::problem2)];
+
+    fn parse(input: &str) -> Result<Inventory, anyhow::Error> {
+        Ok(parse!(input))
+    }
+
+    fn problem1(inventory: &Inventory, _params: &Params) -> Result<Answer, anyhow::Error> {
+        let ans: usize = inventory
+            .ingredients
+            .iter()
+            .filter(|&ingredient| inventory.fresh.contains(*ingredient))
+            .count();
+        Ok(ans.into())
+    }
+
+    fn problem2(inventory: &Inventory, _params: &Params) -> Result<Answer, anyhow::Error> {
+        Ok(inventory.fresh.len().into())
+    }
 }
 
-pub fn problem2(input: &str) -> Result<String, anyhow::Error> {
-    let (ranges, _) = parse!(input);
-    let set = RangeSet::from(ranges);
-    Ok(set.len().to_string())
+pub struct Inventory {
+    // The ranges as listed, which the oracles check one at a time.
+    ranges: Vec<Range>,
+    fresh: RangeSet,
+    ingredients: Vec<u64>,
 }
 
 mod parser {
     use super::*;
     use crate::parser::prelude::*;
 
-    pub fn parse(input: &str) -> IResult<&str, (Vec<Range>, Vec<u64>)> {
+    pub fn parse(input: &str) -> IResult<&str, Inventory> {
         let range =
             separated_pair(uint(), char('-'), uint::<u64>()).map(|(a, b)| Range::new(a, b + 1));
         let ranges = many1(ws_line(range));
         let ingredients = many1(ws_line(uint()));
-        let parser = separated_pair(ranges, multispace1, ingredients);
+        let parser =
+            separated_pair(ranges, multispace1, ingredients).map(|(ranges, ingredients)| {
+                Inventory {
+                    fresh: RangeSet::from(ranges.clone()),
+                    ranges,
+                    ingredients,
+                }
+            });
         ws_all_consuming(parser).parse_complete(input)
     }
 }
 
-#[cfg(test)]
-mod tests {
+mod generator {
+    use rand::{Rng, rngs::StdRng};
+
+    // `size` short ranges, packed closely enough to overlap, then `size`
+    // ingredients.
+    pub fn generate(rng: &mut StdRng, size: usize) -> String {
+        let size = size.max(1);
+        let span = 20 * size as u64;
+
+        let ranges: String = (0..size)
+            .map(|_| {
+                let start = rng.random_range(0..span);
+                let end = start + rng.random_range(0..50);
+                format!("{}-{}\n", start, end)
+            })
+            .collect();
+        let ingredients: String = (0..size)
+            .map(|_| format!("{}\n", rng.random_range(0..span + 50)))
+            .collect();
+        format!("{}\n{}", ranges, ingredients)
+    }
+}
+
+// Checks IDs against every range, one at a time.
+mod oracle {
     use super::*;
 
-    const EXAMPLE_INPUT: &str = "3-5
-    10-14
-    16-20
-    12-18
-
-    1
-    5
-    8
-    11
-    17
-    32";
-
-    #[test]
-    fn problem1_test() {
-        assert_eq!(problem1(EXAMPLE_INPUT).unwrap(), "3")
+    pub fn problem1(inventory: &Inventory, _params: &Params) -> Result<Answer, anyhow::Error> {
+        let ranges = &inventory.ranges;
+        let ans = inventory
+            .ingredients
+            .iter()
+            .filter(|&&id| ranges.iter().any(|r| r.contains(id)))
+            .count();
+        Ok(ans.into())
     }
 
-    #[test]
-    fn problem2_test() {
-        assert_eq!(problem2(EXAMPLE_INPUT).unwrap(), "14")
+    pub fn problem2(inventory: &Inventory, _params: &Params) -> Result<Answer, anyhow::Error> {
+        let ranges = &inventory.ranges;
+        let start = ranges.iter().map(|r| r.start).min().unwrap_or(0);
+        let end = ranges.iter().map(|r| r.end).max().unwrap_or(0);
+        if end.saturating_sub(start) > 1_000_000 {
+            bail!("too many IDs to check one by one");
+        }
+
+        let ans = (start..end)
+            .filter(|&id| ranges.iter().any(|r| r.contains(id)))
+            .count();
+        Ok(ans.into())
     }
 }
@@ -2,18 +2,28 @@ use crate::solutions::prelude::*;
 
 use ahash::HashMap;
 
-pub fn problem1(input: &str) -> Result<String, anyhow::Error> {
-    let problems = parse!(input);
+pub struct Puzzle;
 
-    let ans: u64 = problems.iter().map(|p| p.solve()).sum();
-    Ok(ans.to_string())
-}
+impl Day for Puzzle {
+    type Input<'a> = Vec<Problem>;
+
+    // The problems are read by column, so the padding at the end of short
+    // lines has to stay.
+    const TRIM_TRAILING_SPACE: bool = false;
 
-pub fn problem2(input: &str) -> Result<String, anyhow::Error> {
-    let problems = parse!(input);
+    fn parse(input: &str) -> Result<Vec<Problem>, anyhow::Error> {
+        Ok(parse!(input))
+    }
 
-    let ans: u64 = problems.iter().map(|p| p.solve2()).sum();
-    Ok(ans.to_string())
+    fn problem1(problems: &Vec<Problem>, _params: &Params) -> Result<Answer, anyhow::Error> {
+        let ans: u64 = problems.iter().map(|p| p.solve()).sum();
+        Ok(ans.into())
+    }
+
+    fn problem2(problems: &Vec<Problem>, _params: &Params) -> Result<Answer, anyhow::Error> {
+        let ans: u64 = problems.iter().map(|p| p.solve2()).sum();
+        Ok(ans.into())
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -32,7 +42,7 @@ impl Op {
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
-struct Problem {
+pub struct Problem {
     xs: Vec<Vec<(usize, u8)>>,
     op: Op,
 }
@@ -111,23 +121,3 @@ mod parser {
             .collect()
     }
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const EXAMPLE_INPUT: &str = "123 328  51 64 
- 45 64  387 23 
-  6 98  215 314
-*   +   *   +  ";
-
-    #[test]
-    fn problem1_test() {
-        assert_eq!(problem1(EXAMPLE_INPUT).unwrap(), "4277556")
-    }
-
-    #[test]
-    fn problem2_test() {
-        assert_eq!(problem2(EXAMPLE_INPUT).unwrap(), "3263827")
-    }
-}
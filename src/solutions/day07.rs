@@ -4,18 +4,27 @@ use crate::solutions::prelude::*;
 
 use crate::util::grid::{Direction, Grid, Point};
 
-pub fn problem1(input: &str) -> Result<String, anyhow::Error> {
-    let (splitters_hit, _) = solve(input)?;
-    Ok(splitters_hit.to_string())
-}
+pub struct Puzzle;
+
+impl Day for Puzzle {
+    type Input<'a> = Grid<Cell>;
+
+    fn parse(input: &str) -> Result<Grid<Cell>, anyhow::Error> {
+        Ok(parse!(input))
+    }
 
-pub fn problem2(input: &str) -> Result<String, anyhow::Error> {
-    let (_, total_worlds) = solve(input)?;
-    Ok(total_worlds.to_string())
+    fn problem1(grid: &Grid<Cell>, _params: &Params) -> Result<Answer, anyhow::Error> {
+        let (splitters_hit, _) = solve(grid)?;
+        Ok(splitters_hit.into())
+    }
+
+    fn problem2(grid: &Grid<Cell>, _params: &Params) -> Result<Answer, anyhow::Error> {
+        let (_, total_worlds) = solve(grid)?;
+        Ok(total_worlds.into())
+    }
 }
 
-fn solve(input: &str) -> Result<(usize, usize), anyhow::Error> {
-    let grid = parse!(input);
+fn solve(grid: &Grid<Cell>) -> Result<(usize, usize), anyhow::Error> {
     let start = grid
         .iter_items()
         .find(|(_, c)| **c == Cell::Start)
@@ -26,7 +35,7 @@ fn solve(input: &str) -> Result<(usize, usize), anyhow::Error> {
     let mut total_splitters_hit = 0;
     beams.insert(start, 1);
     loop {
-        let (new_beams, spliters_hit) = step(&grid, &beams);
+        let (new_beams, spliters_hit) = step(grid, &beams);
         total_splitters_hit += spliters_hit;
         if new_beams.is_empty() {
             let total_worlds = beams.values().copied().sum::<usize>();
@@ -66,7 +75,7 @@ fn step(grid: &Grid<Cell>, beams: &AHashMap<Point, usize>) -> (AHashMap<Point, u
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum Cell {
+pub enum Cell {
     Start,
     Empty,
     Splitter,
@@ -87,35 +96,3 @@ mod parser {
         ws_all_consuming(grid).parse_complete(input)
     }
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const EXAMPLE_INPUT: &str = ".......S.......
-    ...............
-    .......^.......
-    ...............
-    ......^.^......
-    ...............
-    .....^.^.^.....
-    ...............
-    ....^.^...^....
-    ...............
-    ...^.^...^.^...
-    ...............
-    ..^...^.....^..
-    ...............
-    .^.^.^.^.^...^.
-    ...............";
-
-    #[test]
-    fn problem1_test() {
-        assert_eq!(problem1(EXAMPLE_INPUT).unwrap(), "21")
-    }
-
-    #[test]
-    fn problem2_test() {
-        assert_eq!(problem2(EXAMPLE_INPUT).unwrap(), "40")
-    }
-}
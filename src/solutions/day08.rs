@@ -2,57 +2,74 @@ use ahash::AHashMap;
 
 use crate::solutions::prelude::*;
 
-pub fn problem1(input: &str) -> Result<String, anyhow::Error> {
-    problem1_(input, 1000)
-}
-
-fn problem1_(input: &str, n: usize) -> Result<String, anyhow::Error> {
-    let points = parse!(input);
+pub struct Puzzle;
 
-    let distances = pairs_by_distance(&points);
+impl Day for Puzzle {
+    type Input<'a> = Vec<Point>;
 
-    let pairs_to_consider = distances[..n.min(distances.len())]
-        .iter()
-        .map(|(pair, _)| *pair);
+    const PARAMS: &'static [Param] = &[Param {
+        name: "n",
+        default: 1000,
+        help: "number of closest pairs to connect",
+    }];
+    const GENERATE: Option<GenerateFn> = Some(generator::generate);
 
-    let mut groups = Groups::default();
-    for pair in pairs_to_consider {
-        groups.connect(&pair.0, &pair.1);
+    fn parse(input: &str) -> Result<Vec<Point>, anyhow::Error> {
+        Ok(parse!(input))
     }
 
-    let sorted_freq = {
-        let mut freq = groups.iter().map(|group| group.len()).collect::<Vec<_>>();
-        freq.sort_unstable();
-        freq
-    };
+    fn problem1(points: &Vec<Point>, params: &Params) -> Result<Answer, anyhow::Error> {
+        let n = usize::try_from(params.get("n")).context("n must not be negative")?;
+        if points.len() < 3 {
+            bail!("need at least 3 junction boxes, got {}", points.len());
+        }
+        let distances = pairs_by_distance(points);
+
+        let pairs_to_consider = distances[..n.min(distances.len())]
+            .iter()
+            .map(|(pair, _)| *pair);
+
+        // Boxes left unconnected are circuits of their own.
+        let mut groups = Groups::default();
+        (0..points.len()).for_each(|i| {
+            groups.add_group(i);
+        });
+        for pair in pairs_to_consider {
+            groups.connect(&pair.0, &pair.1);
+        }
 
-    let ans = sorted_freq[sorted_freq.len() - 3..]
-        .iter()
-        .product::<usize>();
+        let sorted_freq = {
+            let mut freq = groups.iter().map(|group| group.len()).collect::<Vec<_>>();
+            freq.sort_unstable();
+            freq
+        };
 
-    Ok(ans.to_string())
-}
+        // Connecting enough pairs can join everything into one or two
+        // circuits, and then those are the largest.
+        let ans = sorted_freq.iter().rev().take(3).product::<usize>();
 
-pub fn problem2(input: &str) -> Result<String, anyhow::Error> {
-    let points = parse!(input);
+        Ok(ans.into())
+    }
 
-    let distances = pairs_by_distance(&points);
+    fn problem2(points: &Vec<Point>, _params: &Params) -> Result<Answer, anyhow::Error> {
+        let distances = pairs_by_distance(points);
 
-    let pairs_to_consider = distances.iter().map(|(pair, _)| *pair);
+        let pairs_to_consider = distances.iter().map(|(pair, _)| *pair);
 
-    let mut groups = Groups::default();
-    (0..points.len()).for_each(|i| {
-        groups.add_group(i);
-    });
-    for pair in pairs_to_consider {
-        groups.connect(&pair.0, &pair.1);
-        if groups.len == 1 {
-            let ans = points[pair.0].x * points[pair.1].x;
-            return Ok(ans.to_string());
+        let mut groups = Groups::default();
+        (0..points.len()).for_each(|i| {
+            groups.add_group(i);
+        });
+        for pair in pairs_to_consider {
+            groups.connect(&pair.0, &pair.1);
+            if groups.len == 1 {
+                let ans = points[pair.0].x * points[pair.1].x;
+                return Ok(ans.into());
+            }
         }
-    }
 
-    unreachable!()
+        unreachable!()
+    }
 }
 
 fn pairs_by_distance(points: &[Point]) -> Vec<((usize, usize), u64)> {
@@ -67,7 +84,7 @@ fn pairs_by_distance(points: &[Point]) -> Vec<((usize, usize), u64)> {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-struct Point {
+pub struct Point {
     x: u64,
     y: u64,
     z: u64,
@@ -162,38 +179,36 @@ mod parser {
     }
 }
 
+mod generator {
+    use rand::{Rng, rngs::StdRng};
+
+    // `size` points in a 100000 wide cube.
+    pub fn generate(rng: &mut StdRng, size: usize) -> String {
+        (0..size)
+            .map(|_| {
+                let [x, y, z]: [u32; 3] = std::array::from_fn(|_| rng.random_range(0..100_000));
+                format!("{},{},{}\n", x, y, z)
+            })
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const EXAMPLE_INPUT: &str = "162,817,812
-    57,618,57
-    906,360,560
-    592,479,940
-    352,342,300
-    466,668,158
-    542,29,236
-    431,825,988
-    739,650,466
-    52,470,668
-    216,146,977
-    819,987,18
-    117,168,530
-    805,96,715
-    346,949,466
-    970,615,88
-    941,993,340
-    862,61,35
-    984,92,344
-    425,690,689";
-
     #[test]
-    fn problem1_test() {
-        assert_eq!(problem1_(EXAMPLE_INPUT, 10).unwrap(), "40")
-    }
+    fn singleton_circuits_test() {
+        let points = Puzzle::parse("0,0,0\n1,0,0\n10,0,0\n").unwrap();
+        let answer = |n| {
+            let params = Params::resolve(Puzzle::PARAMS, &[("n".to_string(), n)]).unwrap();
+            Puzzle::problem1(&points, &params).unwrap()
+        };
+        assert_eq!(answer(0), Answer::from(1u64));
+        assert_eq!(answer(1), Answer::from(2u64));
+        assert_eq!(answer(3), Answer::from(3u64));
 
-    #[test]
-    fn problem2_test() {
-        assert_eq!(problem2(EXAMPLE_INPUT).unwrap(), "25272")
+        let params = Params::resolve(Puzzle::PARAMS, &[]).unwrap();
+        assert!(Puzzle::problem1(&points[..2].to_vec(), &params).is_err());
     }
 }
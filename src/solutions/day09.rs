@@ -2,40 +2,52 @@ use crate::solutions::prelude::*;
 
 use crate::util::grid::Point;
 
-pub fn problem1(input: &str) -> Result<String, anyhow::Error> {
-    let points = parse!(input);
+pub struct Puzzle;
+
+impl Day for Puzzle {
+    type Input<'a> = Vec<Point>;
+    const GENERATE: Option<GenerateFn> = Some(generator::generate);
+    const ORACLES: [Option<ProblemFn<Self>>; 2] = [
+        None,
+        Some(|points, params| oracle::problem2(points, params)),
+    ];
+
+    fn parse(input: &str) -> Result<Vec<Point>, anyhow::Error> {
+        Ok(parse!(input))
+    }
 
-    let pairs = (0..points.len())
-        .flat_map(|i| (i + 1..points.len()).map(move |j| (i, j)))
-        .map(|(i, j)| (points[i], points[j]));
+    fn problem1(points: &Vec<Point>, _params: &Params) -> Result<Answer, anyhow::Error> {
+        let pairs = (0..points.len())
+            .flat_map(|i| (i + 1..points.len()).map(move |j| (i, j)))
+            .map(|(i, j)| (points[i], points[j]));
 
-    let max_area = pairs
-        .map(|(p1, p2)| (p1.x.abs_diff(p2.x) + 1) * (p1.y.abs_diff(p2.y) + 1))
-        .max()
-        .ok_or_else(|| anyhow::anyhow!("no points provided"))?;
+        let max_area = pairs
+            .map(|(p1, p2)| (p1.x.abs_diff(p2.x) + 1) * (p1.y.abs_diff(p2.y) + 1))
+            .max()
+            .ok_or_else(|| anyhow::anyhow!("no points provided"))?;
 
-    Ok(max_area.to_string())
-}
+        Ok(max_area.into())
+    }
 
-pub fn problem2(input: &str) -> Result<String, anyhow::Error> {
-    let points = parse!(input);
-    let candidates = candidates_by_area(&points);
+    fn problem2(points: &Vec<Point>, _params: &Params) -> Result<Answer, anyhow::Error> {
+        let candidates = candidates_by_area(points);
 
-    let cc = CoordinateCompressor::new(&points);
-    let compressed_points: Vec<_> = points.iter().map(|p| cc.compress(p).unwrap()).collect();
-    let compressed_candidates = candidates
-        .iter()
-        .map(|(a, b, area)| (cc.compress(a).unwrap(), cc.compress(b).unwrap(), *area));
+        let cc = CoordinateCompressor::new(points);
+        let compressed_points: Vec<_> = points.iter().map(|p| cc.compress(p).unwrap()).collect();
+        let compressed_candidates = candidates
+            .iter()
+            .map(|(a, b, area)| (cc.compress(a).unwrap(), cc.compress(b).unwrap(), *area));
 
-    let row_ranges = ranges_per_row(&compressed_points);
+        let row_ranges = ranges_per_row(&compressed_points);
 
-    for (a, b, area) in compressed_candidates {
-        if check_rectangle(a, b, &row_ranges) {
-            return Ok(area.to_string());
+        for (a, b, area) in compressed_candidates {
+            if check_rectangle(a, b, &row_ranges) {
+                return Ok(area.into());
+            }
         }
-    }
 
-    bail!("no solution")
+        bail!("no solution")
+    }
 }
 
 fn check_rectangle(a: Point, b: Point, row_ranges: &[Option<InclusiveRange>]) -> bool {
@@ -102,19 +114,8 @@ impl CoordinateCompressor {
         y_values.sort_unstable();
         y_values.dedup();
 
-        let x_lookup: ahash::AHashMap<_, _> = x_values
-            .iter()
-            .copied()
-            .enumerate()
-            .map(|(i, x)| (x, i))
-            .collect();
-
-        let y_lookup: ahash::AHashMap<_, _> = y_values
-            .iter()
-            .copied()
-            .enumerate()
-            .map(|(i, x)| (x, i))
-            .collect();
+        let x_lookup = Self::lookup(&x_values);
+        let y_lookup = Self::lookup(&y_values);
 
         Self {
             _x_values: x_values,
@@ -124,6 +125,25 @@ impl CoordinateCompressor {
         }
     }
 
+    // Maps sorted, distinct values to consecutive indexes, except that values
+    // that aren't adjacent get an index between them. Otherwise a row between
+    // two corners, which can be narrower than both, would be skipped.
+    fn lookup(values: &[usize]) -> ahash::AHashMap<usize, usize> {
+        let mut next = 0;
+        let mut prev = None;
+        let mut lookup = ahash::AHashMap::with_capacity(values.len());
+        for &v in values {
+            if prev.is_some_and(|p| v > p + 1) {
+                next += 1;
+            }
+            lookup.insert(v, next);
+            next += 1;
+            prev = Some(v);
+        }
+
+        lookup
+    }
+
     fn compress(&self, point: &Point) -> Option<Point> {
         Some(Point::new(
             *self.x_lookup.get(&point.x)?,
@@ -179,26 +199,142 @@ mod parser {
     }
 }
 
-#[cfg(test)]
-mod tests {
+mod generator {
+    use rand::{Rng, rngs::StdRng};
+
+    const WIDTH: usize = 100_000;
+
+    // A polygon with about `size` corners, listed in order around its edge.
+    // It's a stack of rectangular bands, each spanning the middle column, so
+    // every row of it is a single run of tiles.
+    pub fn generate(rng: &mut StdRng, size: usize) -> String {
+        let bands = (size / 4).max(1);
+
+        let mut ys = vec![rng.random_range(0..1000)];
+        for _ in 0..bands {
+            ys.push(ys.last().unwrap() + rng.random_range(1..1000));
+        }
+
+        // Neighbouring bands can't share an edge, or corners would repeat.
+        let mut sides: Vec<(usize, usize)> = Vec::with_capacity(bands);
+        while sides.len() < bands {
+            let side = (
+                rng.random_range(0..WIDTH / 2),
+                rng.random_range(WIDTH / 2 + 1..=WIDTH),
+            );
+            match sides.last() {
+                Some(last) if last.0 == side.0 || last.1 == side.1 => {}
+                _ => sides.push(side),
+            }
+        }
+
+        let right = (0..bands).flat_map(|i| [(sides[i].1, ys[i]), (sides[i].1, ys[i + 1])]);
+        let left = (0..bands)
+            .rev()
+            .flat_map(|i| [(sides[i].0, ys[i + 1]), (sides[i].0, ys[i])]);
+        right
+            .chain(left)
+            .map(|(x, y)| format!("{},{}\n", x, y))
+            .collect()
+    }
+}
+
+// Draws the loop on a grid and floods the outside, then tries every rectangle
+// tile by tile. Runs of rows or columns without a red tile all look the same,
+// so each run is drawn as a single row or column.
+mod oracle {
+    use ahash::AHashMap;
+
     use super::*;
+    use crate::util::grid::Grid;
 
-    const EXAMPLE_INPUT: &str = "7,1
-    11,1
-    11,7
-    9,7
-    9,5
-    2,5
-    2,3
-    7,3";
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    enum Tile {
+        Unknown,
+        Loop,
+        Outside,
+    }
 
-    #[test]
-    fn problem1_test() {
-        assert_eq!(problem1(EXAMPLE_INPUT).unwrap(), "50")
+    pub fn problem2(points: &[Point], _params: &Params) -> Result<Answer, anyhow::Error> {
+        let edges: Vec<_> = points.iter().zip(points.iter().cycle().skip(1)).collect();
+        for &(a, b) in &edges {
+            if (a.x == b.x) == (a.y == b.y) {
+                bail!("{:?} and {:?} aren't joined by a straight line", a, b);
+            }
+        }
+
+        let (xs, width) = squash(points.iter().map(|p| p.x));
+        let (ys, height) = squash(points.iter().map(|p| p.y));
+        let cell = |p: &Point| Point::new(xs[&p.x], ys[&p.y]);
+
+        let mut grid = Grid::new(vec![vec![Tile::Unknown; width]; height]);
+        for (a, b) in edges {
+            let (a, b) = (cell(a), cell(b));
+            for y in a.y.min(b.y)..=a.y.max(b.y) {
+                for x in a.x.min(b.x)..=a.x.max(b.x) {
+                    *grid.get_mut(Point::new(x, y)).unwrap() = Tile::Loop;
+                }
+            }
+        }
+
+        let mut stack = vec![Point::new(0, 0)];
+        while let Some(p) = stack.pop() {
+            if grid.get(p) != Some(&Tile::Unknown) {
+                continue;
+            }
+            *grid.get_mut(p).unwrap() = Tile::Outside;
+            stack.extend(p.iter_adjacent());
+        }
+
+        let mut best = None;
+        for (i, a) in points.iter().enumerate() {
+            for b in &points[i + 1..] {
+                let (ca, cb) = (cell(a), cell(b));
+                let inside = (ca.y.min(cb.y)..=ca.y.max(cb.y)).all(|y| {
+                    (ca.x.min(cb.x)..=ca.x.max(cb.x))
+                        .all(|x| grid.get(Point::new(x, y)) != Some(&Tile::Outside))
+                });
+                if inside {
+                    let area = (a.x.abs_diff(b.x) + 1) * (a.y.abs_diff(b.y) + 1);
+                    best = best.max(Some(area));
+                }
+            }
+        }
+
+        Ok(best.context("no points provided")?.into())
+    }
+
+    // Maps each coordinate to its row or column, leaving a blank one around
+    // the edge so that the outside is connected. Returns the map and the
+    // number of rows or columns.
+    fn squash(values: impl Iterator<Item = usize>) -> (AHashMap<usize, usize>, usize) {
+        let mut values: Vec<_> = values.collect();
+        values.sort_unstable();
+        values.dedup();
+
+        let mut map = AHashMap::new();
+        let mut next = 1;
+        for (i, &v) in values.iter().enumerate() {
+            map.insert(v, next);
+            next += 1;
+            if values.get(i + 1).is_some_and(|&w| w > v + 1) {
+                next += 1;
+            }
+        }
+
+        (map, next + 1)
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
     #[test]
-    fn problem2_test() {
-        assert_eq!(problem2(EXAMPLE_INPUT).unwrap(), "24")
+    fn lookup_test() {
+        let lookup = CoordinateCompressor::lookup(&[1, 2, 5, 9, 10]);
+        let mut indexes: Vec<_> = lookup.into_iter().collect();
+        indexes.sort_unstable();
+        assert_eq!(indexes, [(1, 0), (2, 1), (5, 3), (9, 5), (10, 6)]);
     }
 }
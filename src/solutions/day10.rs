@@ -6,20 +6,38 @@ use good_lp::{
     variable::{ProblemVariables, VariableDefinition},
 };
 
-pub fn problem1(input: &str) -> Result<String, anyhow::Error> {
-    let machines = parse!(input);
+pub struct Puzzle;
 
-    let ans: usize = machines.iter().map(num_buttons_indicators).sum();
+impl Day for Puzzle {
+    type Input<'a> = Vec<Machine>;
+    const GENERATE: Option<GenerateFn> = Some(generator::generate);
+    const ORACLES: [Option<ProblemFn<Self>>; 2] = [
+        Some(|machines, params| oracle::problem1(machines, params)),
+        None,
+    ];
+    const VARIANTS: [&'static [Variant<Self>]; 2] = [
+        &[Variant {
+            name: "gf2",
+            solve: |machines, params| gf2::problem1(machines, params),
+        }],
+        &[],
+    ];
 
-    Ok(ans.to_string())
-}
+    fn parse(input: &str) -> Result<Vec<Machine>, anyhow::Error> {
+        Ok(parse!(input))
+    }
 
-pub fn problem2(input: &str) -> Result<String, anyhow::Error> {
-    let machines = parse!(input);
+    fn problem1(machines: &Vec<Machine>, _params: &Params) -> Result<Answer, anyhow::Error> {
+        let ans: usize = machines.iter().map(num_buttons_indicators).sum();
+
+        Ok(ans.into())
+    }
 
-    let ans: usize = machines.iter().map(num_buttons_joltages).sum();
+    fn problem2(machines: &Vec<Machine>, _params: &Params) -> Result<Answer, anyhow::Error> {
+        let ans: usize = machines.iter().map(num_buttons_joltages).sum();
 
-    Ok(ans.to_string())
+        Ok(ans.into())
+    }
 }
 
 fn num_buttons_indicators(m: &Machine) -> usize {
@@ -87,13 +105,117 @@ fn num_buttons_joltages(m: &Machine) -> usize {
         .sum()
 }
 
+// Solves for the lights as a linear system over GF(2), with one unknown per
+// button. Gaussian elimination leaves some buttons free, and every choice of
+// those gives exactly one solution, so only those choices are tried.
+mod gf2 {
+    use super::*;
+
+    pub fn problem1(machines: &[Machine], _params: &Params) -> Result<Answer, anyhow::Error> {
+        let mut ans = 0;
+        for m in machines {
+            ans += fewest_presses(m)?.context("the lights can't be matched")?;
+        }
+
+        Ok(ans.into())
+    }
+
+    // Each row is a `u64` with a bit per button and one for the target.
+    fn fewest_presses(m: &Machine) -> Result<Option<usize>, anyhow::Error> {
+        let buttons = m.buttons.len();
+        if buttons >= 64 {
+            bail!("{} buttons is too many, at most 63 fit", buttons);
+        }
+        let target = 1u64 << buttons;
+
+        // One row per light: bit `j` is set if button `j` toggles it, and
+        // bit `buttons` if the light has to end up on.
+        let mut rows: Vec<u64> = m
+            .indicators
+            .iter()
+            .enumerate()
+            .map(|(light, &on)| {
+                let toggled = m.buttons.iter().enumerate();
+                let row = toggled
+                    .filter(|(_, b)| b.contains(&light))
+                    .fold(0, |acc, (j, _)| acc | 1 << j);
+                if on { row | target } else { row }
+            })
+            .collect();
+
+        let mut pivots = Vec::new();
+        for col in 0..buttons {
+            let Some(i) = (pivots.len()..rows.len()).find(|&i| rows[i] >> col & 1 == 1) else {
+                continue;
+            };
+            rows.swap(pivots.len(), i);
+            let pivot = rows[pivots.len()];
+            for (k, row) in rows.iter_mut().enumerate() {
+                if k != pivots.len() && *row >> col & 1 == 1 {
+                    *row ^= pivot;
+                }
+            }
+            pivots.push(col);
+        }
+        // A row left with only the target bit reads 0 = 1.
+        if rows[pivots.len()..].contains(&target) {
+            return Ok(None);
+        }
+
+        // Every choice of free buttons is tried, so like the oracle this
+        // gives up rather than run for hours.
+        let free: Vec<usize> = (0..buttons).filter(|x| !pivots.contains(x)).collect();
+        if free.len() > 20 {
+            bail!("{} free buttons is too many to try every set", free.len());
+        }
+        let ans = (0u64..1 << free.len())
+            .map(|choice| {
+                let pressed = free
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| choice >> i & 1 == 1)
+                    .fold(0u64, |acc, (_, &col)| acc | 1 << col);
+                // Each pivot button is pressed if its row's target and the
+                // free buttons it shares with the row don't cancel out.
+                let forced = rows.iter().zip(&pivots).filter(|&(&row, _)| {
+                    let parity = (row & pressed).count_ones() + (row >> buttons) as u32;
+                    parity % 2 == 1
+                });
+                pressed.count_ones() as usize + forced.count()
+            })
+            .min();
+        Ok(ans)
+    }
+}
+
 #[derive(Clone, Debug)]
-struct Machine {
+pub struct Machine {
     indicators: Vec<bool>,
     buttons: Vec<Vec<usize>>,
     joltages: Vec<usize>,
 }
 
+impl std::fmt::Display for Machine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let join = |xs: &[usize]| {
+            xs.iter()
+                .map(|x| x.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+
+        write!(f, "[")?;
+        for &on in &self.indicators {
+            write!(f, "{}", if on { '#' } else { '.' })?;
+        }
+        write!(f, "]")?;
+        for b in &self.buttons {
+            write!(f, " ({})", join(b))?;
+        }
+        write!(f, " {{{}}}", join(&self.joltages))
+    }
+}
+
 mod parser {
     use super::*;
     use crate::parser::prelude::*;
@@ -121,21 +243,111 @@ mod parser {
     }
 }
 
-#[cfg(test)]
-mod tests {
+mod generator {
+    use rand::{Rng, rngs::StdRng, seq::index};
+
     use super::*;
 
-    const EXAMPLE_INPUT: &str = "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
-    [...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}
-    [.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}";
+    // `size` machines. The lights and joltages are made by pressing the
+    // buttons, so every machine can be solved.
+    pub fn generate(rng: &mut StdRng, size: usize) -> String {
+        (0..size).map(|_| machine(rng).to_string() + "\n").collect()
+    }
 
-    #[test]
-    fn problem1_test() {
-        assert_eq!(problem1(EXAMPLE_INPUT).unwrap(), "7")
+    fn machine(rng: &mut StdRng) -> Machine {
+        let lights = rng.random_range(3..=10);
+        let mut buttons: Vec<Vec<usize>> = (0..rng.random_range(2..=lights + 3))
+            .map(|_| {
+                let amount = rng.random_range(1..=lights);
+                index::sample(rng, lights, amount).into_vec()
+            })
+            .collect();
+        // A light no button reaches would have a joltage that can't change.
+        for light in 0..lights {
+            if !buttons.iter().any(|b| b.contains(&light)) {
+                let i = rng.random_range(0..buttons.len());
+                buttons[i].push(light);
+            }
+        }
+        buttons.iter_mut().for_each(|b| b.sort_unstable());
+
+        // Starting from the target would take zero presses, which the search
+        // for the lights doesn't handle.
+        let mut indicators = vec![false; lights];
+        while !indicators.contains(&true) {
+            for b in buttons.iter().filter(|_| rng.random_bool(0.5)) {
+                indicators = apply_button(&indicators, b);
+            }
+        }
+
+        let mut joltages = vec![0; lights];
+        for b in &buttons {
+            let presses = rng.random_range(0..16);
+            for &light in b {
+                joltages[light] += presses;
+            }
+        }
+
+        Machine {
+            indicators,
+            buttons,
+            joltages,
+        }
     }
+}
+
+// Pressing a button twice undoes it, so tries every set of buttons.
+mod oracle {
+    use super::*;
+
+    pub fn problem1(machines: &[Machine], _params: &Params) -> Result<Answer, anyhow::Error> {
+        let mut ans = 0;
+        for m in machines {
+            if m.buttons.len() > 20 {
+                bail!("too many buttons to try every set");
+            }
+
+            let fewest = (0u32..1 << m.buttons.len())
+                .filter(|&set| {
+                    let pressed = m
+                        .buttons
+                        .iter()
+                        .enumerate()
+                        .filter(|(i, _)| set >> i & 1 == 1);
+                    let lights = pressed.fold(vec![false; m.indicators.len()], |acc, (_, b)| {
+                        apply_button(&acc, b)
+                    });
+                    lights == m.indicators
+                })
+                .map(|set| set.count_ones() as usize)
+                .min()
+                .context("the lights can't be matched")?;
+            ans += fewest;
+        }
+
+        Ok(ans.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
     #[test]
-    fn problem2_test() {
-        assert_eq!(problem2(EXAMPLE_INPUT).unwrap(), "33")
+    fn gf2_too_many_buttons_test() {
+        let machine = Machine {
+            indicators: vec![true],
+            buttons: vec![vec![0]; 64],
+            joltages: vec![1],
+        };
+        assert!(gf2::problem1(&[machine], &Params::default()).is_err());
+
+        // Buttons that all toggle the same light leave all but one free.
+        let machine = Machine {
+            indicators: vec![true],
+            buttons: vec![vec![0]; 40],
+            joltages: vec![1],
+        };
+        assert!(gf2::problem1(&[machine], &Params::default()).is_err());
     }
 }
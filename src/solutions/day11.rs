@@ -2,64 +2,74 @@ use crate::solutions::prelude::*;
 
 use ahash::AHashMap;
 
-pub fn problem1(input: &str) -> Result<String, anyhow::Error> {
-    let graph = parse!(input);
+type Graph<'a> = AHashMap<&'a str, Vec<&'a str>>;
 
-    fn rec<'a>(graph: &'a AHashMap<&str, Vec<&str>>, node: &'a str) -> usize {
-        if node == "out" {
-            return 1;
-        }
+pub struct Puzzle;
 
-        let ret: usize = graph
-            .get(node)
-            .unwrap()
-            .iter()
-            .map(|&child| rec(graph, child))
-            .sum();
+impl Day for Puzzle {
+    type Input<'a> = Graph<'a>;
+    const GENERATE: Option<GenerateFn> = Some(generator::generate);
+    const ORACLES: [Option<ProblemFn<Self>>; 2] = [Some(oracle::problem1), Some(oracle::problem2)];
 
-        ret
+    fn parse(input: &str) -> Result<Graph<'_>, anyhow::Error> {
+        Ok(parse!(input))
     }
-    let ans = rec(&graph, "you");
-    Ok(ans.to_string())
-}
 
-pub fn problem2(input: &str) -> Result<String, anyhow::Error> {
-    let graph = parse!(input);
-
-    fn rec<'a>(
-        graph: &'a AHashMap<&str, Vec<&str>>,
-        memo: &mut AHashMap<(&'a str, bool, bool), usize>,
-        node: &'a str,
-        dac: bool,
-        fft: bool,
-    ) -> usize {
-        if node == "out" {
-            if dac && fft {
+    fn problem1(graph: &Graph, _params: &Params) -> Result<Answer, anyhow::Error> {
+        fn rec<'a>(graph: &'a Graph, node: &'a str) -> usize {
+            if node == "out" {
                 return 1;
-            } else {
-                return 0;
             }
-        }
 
-        if let Some(&ans) = memo.get(&(node, dac, fft)) {
-            return ans;
+            let ret: usize = graph
+                .get(node)
+                .unwrap()
+                .iter()
+                .map(|&child| rec(graph, child))
+                .sum();
+
+            ret
         }
+        let ans = rec(graph, "you");
+        Ok(ans.into())
+    }
 
-        let n_dac = dac || node == "dac";
-        let n_fft = fft || node == "fft";
+    fn problem2(graph: &Graph, _params: &Params) -> Result<Answer, anyhow::Error> {
+        fn rec<'a>(
+            graph: &'a Graph,
+            memo: &mut AHashMap<(&'a str, bool, bool), usize>,
+            node: &'a str,
+            dac: bool,
+            fft: bool,
+        ) -> usize {
+            if node == "out" {
+                if dac && fft {
+                    return 1;
+                } else {
+                    return 0;
+                }
+            }
 
-        let ret: usize = graph
-            .get(node)
-            .unwrap()
-            .iter()
-            .map(|&child| rec(graph, memo, child, n_dac, n_fft))
-            .sum();
+            if let Some(&ans) = memo.get(&(node, dac, fft)) {
+                return ans;
+            }
 
-        memo.insert((node, dac, fft), ret);
-        ret
+            let n_dac = dac || node == "dac";
+            let n_fft = fft || node == "fft";
+
+            let ret: usize = graph
+                .get(node)
+                .unwrap()
+                .iter()
+                .map(|&child| rec(graph, memo, child, n_dac, n_fft))
+                .sum();
+
+            memo.insert((node, dac, fft), ret);
+            ret
+        }
+        let ans = rec(graph, &mut AHashMap::new(), "svr", false, false);
+        Ok(ans.into())
     }
-    let ans = rec(&graph, &mut AHashMap::new(), "svr", false, false);
-    Ok(ans.to_string())
 }
 
 mod parser {
@@ -68,7 +78,7 @@ mod parser {
     use super::*;
     use crate::parser::prelude::*;
 
-    pub fn parse(input: &str) -> IResult<&str, AHashMap<&str, Vec<&str>>> {
+    pub fn parse(input: &str) -> IResult<&str, Graph<'_>> {
         let node_list = separated_list1(space1, alpha1);
         let nodedef = separated_pair(alpha1, (char(':'), space0), node_list);
         let graph = fold_many1(ws_line(nodedef), AHashMap::new, |mut acc, (n, l)| {
@@ -79,41 +89,97 @@ mod parser {
     }
 }
 
-#[cfg(test)]
-mod tests {
+mod generator {
+    use ahash::AHashSet;
+    use rand::{Rng, rngs::StdRng, seq::SliceRandom};
+
+    // Devices with exactly two outputs. Paths double at each one, and problem 1
+    // counts them one by one, so this is kept fixed as `size` grows.
+    const FORKS: usize = 12;
+
+    // A DAG of `size` devices plus "out". Each device feeds one or two of the
+    // next few, so everything leads to "out".
+    pub fn generate(rng: &mut StdRng, size: usize) -> String {
+        let n = size.max(4);
+
+        let reserved = ["svr", "you", "dac", "fft", "out"];
+        let mut seen: AHashSet<String> = reserved.iter().map(|x| x.to_string()).collect();
+        let mut names = Vec::with_capacity(n + 1);
+        while names.len() < n {
+            let name: String = (0..3)
+                .map(|_| char::from(rng.random_range(b'a'..=b'z')))
+                .collect();
+            if seen.insert(name.clone()) {
+                names.push(name);
+            }
+        }
+        names.push("out".to_string());
+
+        // "svr" comes first so that it reaches the most, and the others are
+        // placed anywhere after it.
+        let mut order: Vec<usize> = (1..n).collect();
+        order.shuffle(rng);
+        names[0] = "svr".to_string();
+        for (&i, name) in order.iter().zip(["you", "dac", "fft"]) {
+            names[i] = name.to_string();
+        }
+
+        order.shuffle(rng);
+        let forks: AHashSet<usize> = order.iter().copied().take(FORKS).collect();
+
+        let mut lines: Vec<_> = (0..n)
+            .map(|i| {
+                let mut next: Vec<usize> = (i + 1..=(i + 4).min(n)).collect();
+                next.shuffle(rng);
+                next.truncate(if forks.contains(&i) { 2 } else { 1 });
+                let outputs: Vec<_> = next.iter().map(|&j| names[j].as_str()).collect();
+                format!("{}: {}\n", names[i], outputs.join(" "))
+            })
+            .collect();
+        lines.shuffle(rng);
+        lines.concat()
+    }
+}
+
+// Follows every path one at a time, without memoizing anything.
+mod oracle {
     use super::*;
 
-    const EXAMPLE_INPUT: &str = "aaa: you hhh
-    you: bbb ccc
-    bbb: ddd eee
-    ccc: ddd eee fff
-    ddd: ggg
-    eee: out
-    fff: out
-    ggg: out
-    hhh: ccc fff iii
-    iii: out";
-
-    #[test]
-    fn problem1_test() {
-        assert_eq!(problem1(EXAMPLE_INPUT).unwrap(), "5")
+    pub fn problem1(graph: &Graph, _params: &Params) -> Result<Answer, anyhow::Error> {
+        Ok(count_paths(graph, "you", &[])?.into())
+    }
+
+    pub fn problem2(graph: &Graph, _params: &Params) -> Result<Answer, anyhow::Error> {
+        Ok(count_paths(graph, "svr", &["dac", "fft"])?.into())
     }
 
-    #[test]
-    fn problem2_test() {
-        let input = "svr: aaa bbb
-        aaa: fft
-        fft: ccc
-        bbb: tty
-        tty: ccc
-        ccc: ddd eee
-        ddd: hub
-        hub: fff
-        eee: dac
-        dac: fff
-        fff: ggg hhh
-        ggg: out
-        hhh: out";
-        assert_eq!(problem2(input).unwrap(), "2")
+    // Counts the paths from `start` to "out" that pass through all of `via`.
+    fn count_paths(graph: &Graph, start: &str, via: &[&str]) -> Result<usize, anyhow::Error> {
+        let mut count = 0;
+        let mut stack = vec![vec![start]];
+
+        while let Some(path) = stack.pop() {
+            let node = *path.last().unwrap();
+            if node == "out" {
+                if via.iter().all(|x| path.contains(x)) {
+                    count += 1;
+                }
+                continue;
+            }
+            if path.len() > graph.len() {
+                bail!("the devices form a loop");
+            }
+
+            let outputs = graph
+                .get(node)
+                .with_context(|| format!("{} has no outputs", node))?;
+            for &next in outputs {
+                let mut path = path.clone();
+                path.push(next);
+                stack.push(path);
+            }
+        }
+
+        Ok(count)
     }
 }
@@ -1,23 +1,35 @@
 use crate::solutions::prelude::*;
 
-pub fn problem1(input: &str) -> Result<String, anyhow::Error> {
-    let data = parse!(input);
-    let ans = data
-        .iter()
-        .filter(|((l, w), xs)| xs.iter().sum::<usize>() * 7 < l * w)
-        .count();
-
-    Ok(ans.to_string())
-}
+type Tree = ((usize, usize), Vec<usize>);
+
+pub struct Puzzle;
+
+impl Day for Puzzle {
+    type Input<'a> = Vec<Tree>;
+
+    fn parse(input: &str) -> Result<Vec<Tree>, anyhow::Error> {
+        Ok(parse!(input))
+    }
 
-pub fn problem2(_input: &str) -> Result<String, anyhow::Error> {
-    Ok("Finish Decorating the North Pole".to_string())
+    fn problem1(data: &Vec<Tree>, _params: &Params) -> Result<Answer, anyhow::Error> {
+        let ans = data
+            .iter()
+            .filter(|((l, w), xs)| xs.iter().sum::<usize>() * 7 < l * w)
+            .count();
+
+        Ok(ans.into())
+    }
+
+    fn problem2(_data: &Vec<Tree>, _params: &Params) -> Result<Answer, anyhow::Error> {
+        Ok("Finish Decorating the North Pole".into())
+    }
 }
 
 mod parser {
+    use super::*;
     use crate::parser::prelude::*;
 
-    pub fn parse(input: &str) -> IResult<&str, Vec<((usize, usize), Vec<usize>)>> {
+    pub fn parse(input: &str) -> IResult<&str, Vec<Tree>> {
         let skipped_input = input.splitn(30, '\n').last().unwrap();
 
         let num_list = separated_list1(space1, uint());
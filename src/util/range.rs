@@ -60,11 +60,15 @@ impl RangeSet {
         self.ranges.iter().map(|r| r.length()).sum()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.ranges.iter().all(|r| r.length() == 0)
+    }
+
     pub fn contains(&self, n: u64) -> bool {
-        match self.ranges.binary_search_by_key(&n, |r| r.end) {
-            Ok(_) => false,
-            Err(i) => self.ranges.get(i).map(|r| r.contains(n)).unwrap_or(false),
-        }
+        // Touching ranges aren't merged, so `n` can be the end of one range
+        // and the start of the next.
+        let i = self.ranges.partition_point(|r| r.end <= n);
+        self.ranges.get(i).is_some_and(|r| r.contains(n))
     }
 }
 
@@ -91,3 +95,26 @@ impl From<Vec<Range>> for RangeSet {
         Self { ranges }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn range_set_test() {
+        // [1, 3) and [3, 5) touch without overlapping, so stay separate.
+        let set = RangeSet::from(vec![
+            Range::new(3, 5),
+            Range::new(10, 12),
+            Range::new(1, 3),
+            Range::new(11, 15),
+        ]);
+        let contained: Vec<_> = (0..16).filter(|&n| set.contains(n)).collect();
+        assert_eq!(contained, [1, 2, 3, 4, 10, 11, 12, 13, 14]);
+        assert_eq!(set.len(), 9);
+        assert!(!set.is_empty());
+
+        let empty = RangeSet::from(vec![]);
+        assert!(empty.is_empty() && !empty.contains(0));
+    }
+}